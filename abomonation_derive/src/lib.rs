@@ -0,0 +1,417 @@
+//! Custom derives for the `Abomonation` and `Pod` traits.
+//!
+//! The derive implements `Abomonation` for structs and enums by calling the
+//! corresponding methods on each field, in order, much as the implementations
+//! for tuples do. It is re-exported by the `abomonation` crate when its
+//! `derive` feature is enabled.
+//!
+//! A separate `Viewable` derive implements `Viewable`, so that `decode_view`
+//! views decoded values of the type as `&Self`, and a separate `Entomb` derive
+//! implements `Entomb<Self>`, so that references to the type may appear within
+//! views encoded by `encode_as`. They are separate so that types may instead
+//! implement either trait by hand.
+//!
+//! Type parameters are not bounded directly; instead each field type that
+//! mentions a type parameter is required to implement `Abomonation`. This
+//! means that, for example, `PhantomData<T>` fields do not require `T` to
+//! implement `Abomonation`.
+//!
+//! A field may be marked `#[abomonation(skip)]`, in which case its bytes are
+//! copied but it is otherwise ignored. This is only correct for fields which
+//! own no memory, and is intended for plain data without an `Abomonation` impl.
+//!
+//! Every other field type is asserted to implement `Abomonation`, with errors
+//! reported at the field. This rejects references, raw pointers, and other types
+//! whose targets would not be entombed and would dangle after decoding.
+//!
+//! ```compile_fail
+//! #[macro_use]
+//! extern crate abomonation_derive;
+//! extern crate abomonation;
+//!
+//! #[derive(Abomonation)]
+//! struct Borrowed<'a> {
+//!     name: &'a str,
+//! }
+//!
+//! fn main() { }
+//! ```
+//!
+//! # Examples
+//! ```
+//! #[macro_use]
+//! extern crate abomonation_derive;
+//! extern crate abomonation;
+//!
+//! use abomonation::{encode, decode};
+//!
+//! #[derive(Abomonation, Eq, PartialEq)]
+//! enum Shape<T> {
+//!     Point,
+//!     Circle(T, u64),
+//!     Polygon { name: String, points: Vec<(T, T)> },
+//! }
+//!
+//! fn main() {
+//!
+//!     let shape = Shape::Polygon { name: "triangle".to_owned(), points: vec![(0, 0), (0, 1), (1, 0)] };
+//!
+//!     let mut bytes = Vec::new();
+//!     unsafe { encode(&shape, &mut bytes).unwrap(); }
+//!
+//!     if let Some((result, remaining)) = unsafe { decode::<Shape<u32>>(&mut bytes) } {
+//!         assert!(result == &shape);
+//!         assert!(remaining.len() == 0);
+//!     }
+//! }
+//! ```
+
+extern crate proc_macro;
+extern crate proc_macro2;
+#[macro_use]
+extern crate quote;
+extern crate syn;
+
+use std::collections::HashSet;
+
+use proc_macro2::{Span, TokenStream};
+use syn::spanned::Spanned;
+use syn::visit::Visit;
+use syn::{Data, DeriveInput, Error, Fields, Ident, Result, Type};
+
+#[proc_macro_derive(Abomonation, attributes(abomonation))]
+pub fn derive_abomonation(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+    expand(input).unwrap_or_else(|error| error.to_compile_error()).into()
+}
+
+/// Derives `Entomb<Self>`, encoding values of the type as themselves.
+///
+/// The type must also implement `Abomonation`, for example by deriving it.
+#[proc_macro_derive(Entomb)]
+pub fn derive_entomb(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+    expand_entomb(input).into()
+}
+
+/// Derives `Viewable`, viewing decoded values of the type as `&Self`.
+///
+/// The type must also implement `Abomonation`, for example by deriving it.
+#[proc_macro_derive(Viewable)]
+pub fn derive_viewable(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+    expand_viewable(input).into()
+}
+
+/// Derives `Pod` for non-generic `#[repr(C)]` or `#[repr(transparent)]` structs.
+///
+/// Each field type must implement `Pod`, and the size of the struct must equal the sum of
+/// the sizes of its fields, which is checked at compile time and rules out padding.
+#[proc_macro_derive(Pod)]
+pub fn derive_pod(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+    expand_pod(input).unwrap_or_else(|error| error.to_compile_error()).into()
+}
+
+/// A pattern matching one variant (or the struct itself), and the bindings it introduces.
+struct Variant {
+    /// The path naming the variant, e.g. `Name` or `Name::Variant`.
+    path: TokenStream,
+    fields: Vec<Field>,
+    /// How the fields are written in a pattern.
+    style: Style,
+}
+
+struct Field {
+    /// The field name for named fields; `None` for tuple fields.
+    member: Option<Ident>,
+    binding: Ident,
+    ty: Type,
+    skip: bool,
+}
+
+#[derive(Copy, Clone)]
+enum Style { Named, Tuple, Unit }
+
+impl Variant {
+    fn new(path: TokenStream, fields: &Fields) -> Result<Self> {
+        let style = match *fields {
+            Fields::Named(_) => Style::Named,
+            Fields::Unnamed(_) => Style::Tuple,
+            Fields::Unit => Style::Unit,
+        };
+        let mut result = Vec::new();
+        for (index, field) in fields.iter().enumerate() {
+            result.push(Field {
+                member: field.ident.clone(),
+                binding: Ident::new(&format!("__binding_{}", index), Span::call_site()),
+                ty: field.ty.clone(),
+                skip: skipped(&field.attrs)?,
+            });
+        }
+        Ok(Variant { path, fields: result, style })
+    }
+
+    /// A pattern binding each non-skipped field, by `ref` or `ref mut`.
+    fn pattern(&self, mutable: bool) -> TokenStream {
+        let path = &self.path;
+        let bindings = self.fields.iter().map(|field| {
+            let binding = &field.binding;
+            let bound = if field.skip { quote!(_) }
+                        else if mutable { quote!(ref mut #binding) }
+                        else { quote!(ref #binding) };
+            match field.member {
+                Some(ref member) => quote!(#member: #bound),
+                None => bound,
+            }
+        });
+        match self.style {
+            Style::Named => quote!(#path { #(#bindings),* }),
+            Style::Tuple => quote!(#path ( #(#bindings),* )),
+            Style::Unit => quote!(#path),
+        }
+    }
+
+    /// The bindings of fields which are not skipped.
+    fn bindings(&self) -> Vec<&Ident> {
+        self.fields.iter().filter(|field| !field.skip).map(|field| &field.binding).collect()
+    }
+}
+
+/// Determines whether `#[abomonation(skip)]` is present, rejecting unknown options.
+fn skipped(attrs: &[syn::Attribute]) -> Result<bool> {
+    let mut skip = false;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("abomonation")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("skip") {
+                skip = true;
+                Ok(())
+            }
+            else {
+                Err(meta.error("unrecognized abomonation attribute; expected `skip`"))
+            }
+        })?;
+    }
+    Ok(skip)
+}
+
+/// Collects the identifiers of type parameters mentioned by a type.
+struct Mentions<'a> {
+    params: &'a HashSet<Ident>,
+    found: bool,
+}
+
+impl<'a, 'ast> Visit<'ast> for Mentions<'a> {
+    fn visit_path(&mut self, path: &'ast syn::Path) {
+        if let Some(first) = path.segments.first() {
+            if self.params.contains(&first.ident) {
+                self.found = true;
+            }
+        }
+        syn::visit::visit_path(self, path);
+    }
+}
+
+fn expand(input: DeriveInput) -> Result<TokenStream> {
+
+    let name = &input.ident;
+    let variants = match input.data {
+        Data::Struct(ref data) => vec![Variant::new(quote!(#name), &data.fields)?],
+        Data::Enum(ref data) => {
+            data.variants
+                .iter()
+                .map(|variant| {
+                    if skipped(&variant.attrs)? {
+                        return Err(Error::new_spanned(variant, "`#[abomonation(skip)]` applies only to fields"));
+                    }
+                    let ident = &variant.ident;
+                    Variant::new(quote!(#name::#ident), &variant.fields)
+                })
+                .collect::<Result<Vec<_>>>()?
+        },
+        Data::Union(_) => {
+            return Err(Error::new(Span::call_site(), "`Abomonation` cannot be derived for unions"));
+        },
+    };
+
+    // Require `Abomonation` of each field type mentioning a type parameter.
+    let params = input.generics.type_params().map(|param| param.ident.clone()).collect::<HashSet<_>>();
+    let mut generics = input.generics.clone();
+    {
+        let where_clause = generics.make_where_clause();
+        let mut bounded = HashSet::new();
+        for field in variants.iter().flat_map(|variant| variant.fields.iter()).filter(|field| !field.skip) {
+            let mut mentions = Mentions { params: &params, found: false };
+            mentions.visit_type(&field.ty);
+            let ty = &field.ty;
+            if mentions.found && bounded.insert(quote!(#ty).to_string()) {
+                where_clause.predicates.push(syn::parse_quote!(#ty: ::abomonation::Abomonation));
+            }
+        }
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    // Assert that each field type implements `Abomonation`, reporting errors at the field.
+    let assertions = variants
+        .iter()
+        .flat_map(|variant| variant.fields.iter())
+        .filter(|field| !field.skip)
+        .map(|field| {
+            let ty = &field.ty;
+            quote_spanned!(ty.span() => __assert_abomonation::<#ty>();)
+        });
+
+    // Values own memory only if some non-skipped field type might.
+    let indirections = variants
+        .iter()
+        .flat_map(|variant| variant.fields.iter())
+        .filter(|field| !field.skip)
+        .map(|field| {
+            let ty = &field.ty;
+            quote!(<#ty as ::abomonation::Abomonation>::HAS_INDIRECTION)
+        });
+
+    let entomb = variants.iter().map(|variant| {
+        let pattern = variant.pattern(false);
+        let bindings = variant.bindings();
+        quote! { #pattern => { #( ::abomonation::Abomonation::entomb(#bindings, write)?; )* } }
+    });
+    let exhume = variants.iter().map(|variant| {
+        let pattern = variant.pattern(true);
+        let bindings = variant.bindings();
+        quote! { #pattern => { #( let temp = bytes; bytes = ::abomonation::Abomonation::exhume(#bindings, temp)?; )* } }
+    });
+    let extent = variants.iter().map(|variant| {
+        let pattern = variant.pattern(false);
+        let bindings = variant.bindings();
+        quote! { #pattern => { #( size += ::abomonation::Abomonation::extent(#bindings); )* } }
+    });
+
+    Ok(quote! {
+        const _: () = {
+            #[allow(dead_code)]
+            fn __assert_abomonation<T: ?Sized + ::abomonation::Abomonation>() { }
+            #[allow(dead_code)]
+            fn __assert_fields #impl_generics (_: &#name #ty_generics) #where_clause { #(#assertions)* }
+        };
+
+        impl #impl_generics ::abomonation::Abomonation for #name #ty_generics #where_clause {
+            const HAS_INDIRECTION: bool = false #(|| #indirections)*;
+            #[inline]
+            #[allow(unused_variables, unreachable_code)]
+            unsafe fn entomb<__W: ::std::io::Write>(&self, write: &mut __W) -> ::std::io::Result<()> {
+                match *self { #(#entomb)* }
+                Ok(())
+            }
+            #[inline]
+            #[allow(unused_mut, unreachable_code)]
+            unsafe fn exhume<'__a, '__b>(&'__a mut self, mut bytes: &'__b mut [u8]) -> Option<&'__b mut [u8]> {
+                match *self { #(#exhume)* }
+                Some(bytes)
+            }
+            #[inline]
+            #[allow(unused_mut, unreachable_code)]
+            fn extent(&self) -> usize {
+                let mut size = 0;
+                match *self { #(#extent)* }
+                size
+            }
+        }
+    })
+}
+
+/// The generics of `input`, further requiring that the type implement `Abomonation`.
+fn abomonable_generics(input: &DeriveInput) -> syn::Generics {
+    let name = &input.ident;
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+    let mut generics = input.generics.clone();
+    generics.make_where_clause().predicates.push(syn::parse_quote!(#name #ty_generics: ::abomonation::Abomonation));
+    generics
+}
+
+fn expand_entomb(input: DeriveInput) -> TokenStream {
+
+    let name = &input.ident;
+    let generics = abomonable_generics(&input);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    quote! {
+        // Allows the type to appear within borrowed views, for example as `&T` encoded as `T`.
+        unsafe impl #impl_generics ::abomonation::Entomb<#name #ty_generics> for #name #ty_generics #where_clause {
+            #[inline]
+            unsafe fn stand_in(&self) -> ::std::mem::ManuallyDrop<Self> {
+                ::std::mem::ManuallyDrop::new(::std::ptr::read(self))
+            }
+            #[inline]
+            unsafe fn entomb_as<__W: ::std::io::Write>(&self, write: &mut __W) -> ::std::io::Result<()> {
+                ::abomonation::Abomonation::entomb(self, write)
+            }
+            #[inline]
+            fn extent_as(&self) -> usize {
+                ::abomonation::Abomonation::extent(self)
+            }
+        }
+    }
+}
+
+fn expand_viewable(input: DeriveInput) -> TokenStream {
+
+    let name = &input.ident;
+    let generics = abomonable_generics(&input);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    quote! {
+        // Views the decoded value as a reference, for example as the elements of a `Slice`.
+        impl #impl_generics ::abomonation::Viewable for #name #ty_generics #where_clause {
+            type View<'__a> = &'__a Self where Self: '__a;
+            #[inline]
+            fn view(&self) -> &Self { self }
+        }
+    }
+}
+
+fn expand_pod(input: DeriveInput) -> Result<TokenStream> {
+
+    let name = &input.ident;
+    let fields = match input.data {
+        Data::Struct(ref data) => &data.fields,
+        _ => return Err(Error::new_spanned(name, "`Pod` can only be derived for structs")),
+    };
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(&input.generics, "`Pod` cannot be derived for generic structs"));
+    }
+
+    // The layout of the default representation is unspecified, and may change.
+    let mut stable = false;
+    for attr in input.attrs.iter().filter(|attr| attr.path().is_ident("repr")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("C") || meta.path.is_ident("transparent") {
+                stable = true;
+            }
+            else if meta.input.peek(syn::token::Paren) {
+                // e.g. `align(8)` or `packed(2)`; any resulting padding is detected below.
+                meta.input.parse::<proc_macro2::TokenTree>()?;
+            }
+            Ok(())
+        })?;
+    }
+    if !stable {
+        return Err(Error::new_spanned(name, "`Pod` requires `#[repr(C)]` or `#[repr(transparent)]`"));
+    }
+
+    let types = fields.iter().map(|field| &field.ty).collect::<Vec<_>>();
+    let assertions = types.iter().map(|ty| quote_spanned!(ty.span() => __assert_pod::<#ty>();));
+    let message = syn::LitStr::new(&format!("`{}` contains padding bytes, and cannot be `Pod`", name), Span::call_site());
+
+    Ok(quote! {
+        const _: () = {
+            #[allow(dead_code)]
+            fn __assert_pod<T: ::abomonation::Pod>() { }
+            #[allow(dead_code)]
+            fn __assert_fields() { #(#assertions)* }
+            assert!(::std::mem::size_of::<#name>() == 0 #(+ ::std::mem::size_of::<#types>())*, #message);
+        };
+
+        unsafe impl ::abomonation::Pod for #name { }
+    })
+}
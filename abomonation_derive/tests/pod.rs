@@ -0,0 +1,71 @@
+extern crate abomonation;
+
+use abomonation::*;
+use abomonation::abomonated::AlignedBytes;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Pod)]
+#[repr(C)]
+pub struct Point {
+    x: u32,
+    y: u32,
+    tags: [u16; 4],
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Pod)]
+#[repr(transparent)]
+pub struct Meters(f64);
+
+#[derive(Copy, Clone, Debug, PartialEq, Pod)]
+#[repr(C)]
+pub struct Nested {
+    point: Point,
+    length: Meters,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Pod)]
+#[repr(C, packed)]
+pub struct Packed {
+    a: u8,
+    b: u64,
+}
+
+#[test]
+fn test_pod_roundtrip() {
+    let record = Nested { point: Point { x: 1, y: 2, tags: [3, 4, 5, 6] }, length: Meters(7.5) };
+
+    let mut bytes = Vec::new();
+    encode_pod(&record, &mut bytes).unwrap();
+    assert_eq!(bytes.len(), std::mem::size_of::<Nested>());
+
+    let mut aligned = AlignedBytes::new(bytes.len(), std::mem::align_of::<Nested>());
+    aligned.copy_from_slice(&bytes);
+    let (result, rest) = decode_pod::<Nested>(&aligned).unwrap();
+    assert_eq!(result, &record);
+    assert!(rest.is_empty());
+}
+
+#[test]
+fn test_pod_packed() {
+    let records = [Packed { a: 1, b: 2 }; 16];
+    let mut bytes = Vec::new();
+    encode_pod_slice(&records[..], &mut bytes).unwrap();
+    assert_eq!(bytes.len(), 9 * 16);
+    assert_eq!(decode_pod_slice::<Packed>(&bytes), Some(&records[..]));
+}
+
+#[test]
+fn test_pod_slice_checks() {
+    let points = [Point { x: 0, y: 1, tags: [2; 4] }; 8];
+    let mut bytes = Vec::new();
+    encode_pod_slice(&points[..], &mut bytes).unwrap();
+
+    let mut aligned = AlignedBytes::new(bytes.len() + 4, std::mem::align_of::<Point>());
+    aligned[..bytes.len()].copy_from_slice(&bytes);
+
+    assert_eq!(decode_pod_slice::<Point>(&aligned[..bytes.len()]), Some(&points[..]));
+    // lengths must be a multiple of the element size.
+    assert_eq!(decode_pod_slice::<Point>(&aligned[..bytes.len() - 1]), None);
+    // starts must be aligned.
+    assert_eq!(decode_pod_slice::<Point>(&aligned[2 .. 2 + 16 * 7]), None);
+    assert!(decode_pod::<Point>(&aligned[1..]).is_none());
+}
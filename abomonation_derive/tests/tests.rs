@@ -0,0 +1,204 @@
+extern crate abomonation;
+
+use std::marker::PhantomData;
+
+use abomonation::*;
+
+#[derive(Eq, PartialEq, Abomonation, Entomb, Viewable)]
+pub struct Struct {
+    a: String,
+    b: u64,
+    c: Vec<u8>,
+}
+
+#[derive(Eq, PartialEq, Abomonation)]
+pub struct EmptyStruct;
+
+#[derive(Eq, PartialEq, Abomonation)]
+pub struct TupleStruct(String, u64, Vec<u8>);
+
+#[derive(Eq, PartialEq, Abomonation)]
+pub struct GenericStruct<T, U> {
+    a: T,
+    b: Vec<(T, U)>,
+}
+
+#[derive(Eq, PartialEq, Abomonation)]
+pub struct PhantomStruct<T> {
+    a: String,
+    b: PhantomData<T>,
+}
+
+#[derive(Eq, PartialEq, Abomonation)]
+pub enum BasicEnum {
+    Apples,
+    Pears,
+    Chicken,
+}
+
+#[derive(Eq, PartialEq, Abomonation, Viewable)]
+pub enum DataEnum<T> {
+    A(String, u64, Vec<u8>),
+    B,
+    C { name: String, items: Vec<T> },
+}
+
+#[derive(Eq, PartialEq, Abomonation)]
+pub enum EmptyEnum { }
+
+#[derive(Eq, PartialEq, Copy, Clone)]
+pub struct NotAbomonatable(u64);
+
+#[derive(Eq, PartialEq, Abomonation)]
+pub struct SkipStruct {
+    a: String,
+    #[abomonation(skip)]
+    b: NotAbomonatable,
+    c: Vec<u8>,
+}
+
+#[derive(Eq, PartialEq, Abomonation)]
+pub struct LifetimeStruct<'a, W> {
+    a: Vec<W>,
+    b: PhantomData<&'a W>,
+}
+
+fn _struct() -> Struct { Struct { a: "test".to_owned(), b: 0, c: vec![0, 1, 2, 3] } }
+fn _tuple_struct() -> TupleStruct { TupleStruct("test".to_owned(), 0, vec![0, 1, 2]) }
+fn _generic_struct() -> GenericStruct<u64, String> { GenericStruct { a: 7, b: vec![(0, "test".to_owned()); 32] } }
+fn _phantom_struct() -> PhantomStruct<NotAbomonatable> { PhantomStruct { a: "test".to_owned(), b: PhantomData } }
+fn _data_enums() -> Vec<DataEnum<String>> {
+    vec![
+        DataEnum::A("test".to_owned(), 0, vec![0, 1, 2, 3]),
+        DataEnum::B,
+        DataEnum::C { name: "variants".to_owned(), items: vec!["grawwwwrr!".to_owned(); 32] },
+    ]
+}
+fn _skip_struct() -> SkipStruct { SkipStruct { a: "test".to_owned(), b: NotAbomonatable(7), c: vec![0, 1, 2] } }
+fn _lifetime_struct() -> LifetimeStruct<'static, String> { LifetimeStruct { a: vec!["test".to_owned(); 8], b: PhantomData } }
+
+#[test] fn test_struct() { _test_pass(_struct()); }
+#[test] fn test_empty_struct() { _test_pass(EmptyStruct); }
+#[test] fn test_tuple_struct() { _test_pass(_tuple_struct()); }
+#[test] fn test_generic_struct() { _test_pass(_generic_struct()); }
+#[test] fn test_phantom_struct() { _test_pass(_phantom_struct()); }
+#[test] fn test_basic_enum() { _test_pass(vec![BasicEnum::Apples, BasicEnum::Pears, BasicEnum::Chicken]); }
+#[test] fn test_data_enum() { _test_pass(_data_enums()); }
+#[test] fn test_skip_struct() { _test_pass(_skip_struct()); }
+#[test] fn test_lifetime_struct() { _test_pass(_lifetime_struct()); }
+
+#[test] fn test_struct_fail() { _test_fail(_struct()); }
+#[test] fn test_tuple_struct_fail() { _test_fail(_tuple_struct()); }
+#[test] fn test_generic_struct_fail() { _test_fail(_generic_struct()); }
+#[test] fn test_data_enum_fail() { _test_fail(_data_enums()); }
+#[test] fn test_skip_struct_fail() { _test_fail(_skip_struct()); }
+
+#[test] fn test_struct_size() { _test_size(_struct()); }
+#[test] fn test_empty_struct_size() { _test_size(EmptyStruct); }
+#[test] fn test_tuple_struct_size() { _test_size(_tuple_struct()); }
+#[test] fn test_generic_struct_size() { _test_size(_generic_struct()); }
+#[test] fn test_data_enum_size() { _test_size(_data_enums()); }
+#[test] fn test_skip_struct_size() { _test_size(_skip_struct()); }
+
+fn _test_pass<T: Abomonation+Eq>(record: T) {
+    let mut bytes = Vec::new();
+    unsafe { encode(&record, &mut bytes).unwrap(); }
+    {
+        let (result, rest) = unsafe { decode::<T>(&mut bytes[..]) }.unwrap();
+        assert!(&record == result);
+        assert!(rest.is_empty());
+    }
+}
+
+fn _test_fail<T: Abomonation>(record: T) {
+    let mut bytes = Vec::new();
+    unsafe { encode(&record, &mut bytes).unwrap(); }
+    bytes.pop();
+    assert!(unsafe { decode::<T>(&mut bytes[..]) }.is_none());
+}
+
+fn _test_size<T: Abomonation>(record: T) {
+    let mut bytes = Vec::new();
+    unsafe { encode(&record, &mut bytes).unwrap(); }
+    assert_eq!(bytes.len(), measure(&record));
+}
+
+#[test]
+fn test_encode_as() {
+    let records = (0 .. 32).map(|_| _struct()).collect::<Vec<_>>();
+    let view = records.iter().collect::<Vec<&Struct>>();
+    let mut bytes = Vec::new();
+    unsafe { encode_as::<Vec<Struct>, _, _>(&view, &mut bytes).unwrap(); }
+    assert_eq!(bytes.len(), measure(&records));
+    let (result, rest) = unsafe { decode::<Vec<Struct>>(&mut bytes[..]) }.unwrap();
+    assert!(result == &records);
+    assert!(rest.is_empty());
+}
+
+#[test]
+fn test_encode_as_by_hand() {
+    // encodes as a `Named`, without deriving `Entomb`.
+    #[derive(Eq, PartialEq, Abomonation)]
+    struct Named { name: String }
+    unsafe impl Entomb<Named> for str {
+        unsafe fn stand_in(&self) -> std::mem::ManuallyDrop<Named> {
+            std::mem::ManuallyDrop::new(Named { name: String::from_raw_parts(self.as_ptr() as *mut u8, self.len(), self.len()) })
+        }
+        unsafe fn entomb_as<W: std::io::Write>(&self, write: &mut W) -> std::io::Result<()> { write.write_all(self.as_bytes()) }
+        fn extent_as(&self) -> usize { self.len() }
+    }
+
+    let mut bytes = Vec::new();
+    unsafe { encode_as::<Vec<Named>, _, _>(&["a", "bc"][..], &mut bytes).unwrap(); }
+    let (result, _) = unsafe { decode::<Vec<Named>>(&mut bytes[..]) }.unwrap();
+    assert!(result == &vec![Named { name: "a".to_string() }, Named { name: "bc".to_string() }]);
+}
+
+#[test]
+fn test_has_indirection() {
+    #[derive(Abomonation)]
+    struct Plain { _a: u64, _b: [u32; 4], _c: Option<(u8, u16)> }
+    #[derive(Abomonation)]
+    enum PlainEnum { _A(u64), _B { _b: char } }
+
+    const { assert!(!Plain::HAS_INDIRECTION) };
+    const { assert!(!PlainEnum::HAS_INDIRECTION) };
+    const { assert!(!EmptyStruct::HAS_INDIRECTION) };
+    const { assert!(!BasicEnum::HAS_INDIRECTION) };
+    const { assert!(GenericStruct::<u64, u64>::HAS_INDIRECTION) };
+    const { assert!(PhantomStruct::<NotAbomonatable>::HAS_INDIRECTION) };
+    const { assert!(Struct::HAS_INDIRECTION) };
+    const { assert!(DataEnum::<u64>::HAS_INDIRECTION) };
+}
+
+#[test]
+fn test_decode_view() {
+    let records = (0 .. 32).map(|i| (i as u64, _struct(), _data_enums())).collect::<Vec<_>>();
+    let mut bytes = Vec::new();
+    unsafe { encode(&records, &mut bytes).unwrap(); }
+    let (view, rest) = unsafe { decode_view::<Vec<(u64, Struct, Vec<DataEnum<String>>)>>(&mut bytes[..]) }.unwrap();
+    assert!(rest.is_empty());
+    for (record, (number, structure, enums)) in records.iter().zip(view.iter()) {
+        let structure: &Struct = structure;
+        assert_eq!(number, record.0);
+        assert!(structure == &record.1);
+        assert!(enums.iter().eq(record.2.iter()));
+    }
+}
+
+#[test]
+fn test_decode_view_by_hand() {
+    // views as a `&str`, without deriving `Viewable`.
+    #[derive(Eq, PartialEq, Abomonation)]
+    struct Named { name: String }
+    impl Viewable for Named {
+        type View<'a> = &'a str;
+        fn view(&self) -> &str { &self.name }
+    }
+
+    let mut bytes = Vec::new();
+    unsafe { encode(&vec![Named { name: "a".to_string() }, Named { name: "bc".to_string() }], &mut bytes).unwrap(); }
+    let (view, _) = unsafe { decode_view::<Vec<Named>>(&mut bytes[..]) }.unwrap();
+    assert!(view.iter().eq(vec!["a", "bc"]));
+}
+
@@ -1,18 +1,27 @@
 
-use std::mem::transmute;
 use std::marker::PhantomData;
 use std::ops::{Deref, DerefMut};
+use std::ptr::NonNull;
+use std::sync::Arc;
+use std::alloc::{self, Layout};
 
-use super::{Abomonation, decode};
+use super::{Abomonation, encode, decode};
+use error::EncodeError;
+use limits::{DecodeLimits, DecodeError, decode_with_limits};
+use padding::{self, Counted};
 
 /// A type wrapping owned decoded abomonated data.
 ///
 /// This type ensures that decoding and pointer correction has already happened,
-/// and implements `Deref<Target=T>` using a pointer cast (transmute).
+/// and implements `Deref<Target=T>` through a pointer to the decoded root.
 ///
-/// #Safety
+/// The pointer is taken from the mutably borrowed bytes when they are decoded,
+/// so that updates through `Cell` and atomic values reached by `Deref` write
+/// through a pointer permitted to write to the bytes.
 ///
-/// The safety of this type, and in particular its `transute` implementation of
+/// # Safety
+///
+/// The safety of this type, and in particular its pointer-based implementation of
 /// the `Deref` trait, relies on the owned bytes not being externally mutated
 /// once provided. You could imagine a new type implementing `DerefMut` as required,
 /// but which also retains the ability (e.g. through `RefCell`) to mutate the bytes.
@@ -23,7 +32,7 @@ use super::{Abomonation, decode};
 /// Otherwise moving an instance of `Abomonated<T, S>` may invalidate decoded
 /// pointers, and everything goes badly.
 ///
-/// #Examples
+/// # Examples
 ///
 /// ```
 /// use std::ops::Deref;
@@ -51,6 +60,8 @@ use super::{Abomonation, decode};
 /// ```
 pub struct Abomonated<T, S: DerefMut<Target=[u8]>> {
     phantom: PhantomData<T>,
+    /// The decoded root, at the start of `decoded`.
+    pointer: *mut T,
     decoded: S,
 }
 
@@ -61,7 +72,7 @@ impl<T: Abomonation, S: DerefMut<Target=[u8]>> Abomonated<T, S> {
     /// This method will return `None` if it is unable to decode the data with
     /// type `T`.
     ///
-    /// #Examples
+    /// # Examples
     ///
     /// ```
     /// use std::ops::Deref;
@@ -88,19 +99,32 @@ impl<T: Abomonation, S: DerefMut<Target=[u8]>> Abomonated<T, S> {
     /// }
     /// ```
     ///
-    /// #Safety
+    /// # Safety
     ///
     /// The type `S` must have its bytes at a fixed location, which will
     /// not change if the `bytes: S` instance is moved. Good examples are
     /// `Vec<u8>` whereas bad examples are `[u8; 16]`.
+    ///
+    /// If `T` contains `Rc` or `Arc` values, clones of them are not tied to the
+    /// lifetime of the bytes, and must not be used once the `Abomonated` (or any
+    /// `Projected` handle derived from it) has been dropped.
+    ///
+    /// If `T` contains `Cell` or atomic values, they must not be updated while
+    /// the bytes returned by `as_bytes` (of the `Abomonated`, or of any `Projected`
+    /// handle derived from it) are in use, as those would then observe memory
+    /// mutated through another reference.
     pub unsafe fn new(mut bytes: S) -> Option<Self> {
 
+        // decodes through the pointer retained for later access.
+        let (pointer, length) = { let slice = bytes.deref_mut(); (slice.as_mut_ptr(), slice.len()) };
+
         // performs the underlying pointer correction, indicates success.
-        let decoded = decode::<T>(bytes.deref_mut()).is_some();
+        let decoded = decode::<T>(std::slice::from_raw_parts_mut(pointer, length)).is_some();
 
         if decoded {
             Some(Abomonated {
                 phantom: PhantomData,
+                pointer: pointer as *mut T,
                 decoded: bytes,
             })
         }
@@ -108,12 +132,212 @@ impl<T: Abomonation, S: DerefMut<Target=[u8]>> Abomonated<T, S> {
             None
         }
     }
+
+    /// Attempts to create decoded data from owned mutable bytes, within `limits`.
+    ///
+    /// This method decodes as `decode_with_limits` does, and fails with its error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use abomonation::{encode, DecodeLimits, DecodeError};
+    /// use abomonation::abomonated::Abomonated;
+    ///
+    /// let vector = (0..256u64).map(|i| (i, format!("{}", i)))
+    ///                         .collect::<Vec<_>>();
+    ///
+    /// let mut bytes = Vec::new();
+    /// unsafe { encode(&vector, &mut bytes).unwrap(); }
+    ///
+    /// let limits = DecodeLimits { max_bytes: 1024, ..DecodeLimits::default() };
+    /// let result = unsafe { Abomonated::<Vec<(u64, String)>,_>::new_with_limits(bytes, &limits) };
+    /// assert_eq!(result.err(), Some(DecodeError::BytesExceeded));
+    /// ```
+    ///
+    /// # Safety
+    ///
+    /// This method is unsafe for the same reasons as `new`.
+    pub unsafe fn new_with_limits(mut bytes: S, limits: &DecodeLimits) -> Result<Self, DecodeError> {
+        let (pointer, length) = { let slice = bytes.deref_mut(); (slice.as_mut_ptr(), slice.len()) };
+        decode_with_limits::<T>(std::slice::from_raw_parts_mut(pointer, length), limits)?;
+        Ok(Abomonated { phantom: PhantomData, pointer: pointer as *mut T, decoded: bytes })
+    }
+}
+
+impl<T: Abomonation> Abomonated<T, AlignedBytes> {
+
+    /// Encodes `typed` into freshly allocated storage and decodes it in place.
+    ///
+    /// The data `typed` owns are padded so that each part of it is aligned at
+    /// the offset where it lands, and the storage is allocated once, aligned to
+    /// the greatest alignment of any part. A first pass over `typed` measures the
+    /// padded bytes. The bytes are produced by this host from a valid `T`, and
+    /// are only exposed mutably through `as_mut`, which permits in-place updates
+    /// that keep them valid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use abomonation::abomonated::Abomonated;
+    ///
+    /// let vector = (0..256u64).map(|i| (format!("{}", i), vec![i as u128; 3]))
+    ///                         .collect::<Vec<_>>();
+    ///
+    /// let decoded = unsafe { Abomonated::from_value(&vector) }.unwrap();
+    /// assert!(&*decoded == &vector);
+    /// ```
+    ///
+    /// # Safety
+    ///
+    /// The `Abomonation` implementation of `T` (and of every type it contains) must
+    /// entomb all data that `T` owns, and `exhume` must correct every pointer to them.
+    /// Implementations that do not, such as an empty `impl Abomonation` for a type
+    /// owning a `Vec`, or a derived implementation with an owned field marked
+    /// `#[abomonation(skip)]`, leave pointers into memory that `typed` owns, which
+    /// may be freed while the result is in use.
+    ///
+    /// Values of `Cell` and atomic types within `typed` must not be updated
+    /// while the bytes returned by `as_bytes` are in use, as for `new`.
+    ///
+    /// # Errors
+    ///
+    /// This method fails with `EncodeError::Io` if `typed` contains `Rc` or
+    /// `Arc` values, as clones of them could outlive the bytes. It fails with
+    /// `EncodeError::ExtentMismatch` if the two passes over `typed` write
+    /// different numbers of bytes, or the bytes fail to decode, either of which
+    /// indicates an incorrect `Abomonation` implementation.
+    pub unsafe fn from_value(typed: &T) -> Result<Self, EncodeError> {
+        let mismatch = || EncodeError::ExtentMismatch { type_name: std::any::type_name::<T>() };
+        let (length, align) = {
+            let scope = padding::Scope::encoding();
+            encode(typed, &mut Counted(std::io::sink()))?;
+            scope.written()
+        };
+        let mut bytes = AlignedBytes::new(length, align.max(std::mem::align_of::<T>()));
+        {
+            let _scope = padding::Scope::encoding();
+            let mut cursor = &mut bytes[..];
+            // writing past the end of the bytes fails, as does any failure the first pass avoided.
+            encode(typed, &mut Counted(&mut cursor)).map_err(|_| mismatch())?;
+            if !cursor.is_empty() { return Err(mismatch()); }
+        }
+        let _scope = padding::Scope::decoding();
+        Abomonated::new(bytes).ok_or_else(mismatch)
+    }
 }
 
 impl<T, S: DerefMut<Target=[u8]>> Abomonated<T, S> {
     pub fn as_bytes(&self) -> &[u8] {
         &self.decoded
     }
+
+    /// Projects the decoded data to a part of itself, retaining the owned bytes.
+    ///
+    /// The closure must return a reference that borrows from its argument (or
+    /// is `'static`), which ensures that the result cannot outlive the bytes
+    /// that `Projected` keeps alive. No data are copied.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use abomonation::encode;
+    /// use abomonation::abomonated::Abomonated;
+    ///
+    /// let record = (7u64, vec![format!("a"), format!("b")]);
+    ///
+    /// let mut bytes = Vec::new();
+    /// unsafe { encode(&record, &mut bytes).unwrap(); }
+    ///
+    /// let decoded = unsafe { Abomonated::<(u64, Vec<String>),_>::new(bytes) }.unwrap();
+    /// let rows = decoded.map(|x| &x.1);
+    /// assert_eq!(&*rows, &record.1);
+    ///
+    /// // projections can be further projected.
+    /// let second = rows.map(|x| &x[1][..]);
+    /// assert_eq!(&*second, "b");
+    /// ```
+    pub fn map<U: ?Sized, F>(self, logic: F) -> Projected<U, S>
+    where
+        F: for<'a> FnOnce(&'a T) -> &'a U,
+    {
+        let pointer: *const U = logic(self.deref());
+        Projected {
+            pointer,
+            decoded: self.decoded,
+        }
+    }
+
+    /// Provides controlled mutable access to the decoded data.
+    ///
+    /// The result only allows mutations which cannot reallocate or free memory
+    /// backed by the owned bytes; see `AbomonatedMut` for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use abomonation::abomonated::Abomonated;
+    ///
+    /// let record = (0u64, vec![(1u32, format!("a")), (2u32, format!("b"))]);
+    /// let mut decoded = unsafe { Abomonated::from_value(&record) }.unwrap();
+    ///
+    /// {
+    ///     let (mut count, mut rows) = decoded.as_mut().fields();
+    ///     *count += 1;
+    ///     for row in rows.iter_mut() {
+    ///         *row.fields().0 *= 10;
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(decoded.0, 1);
+    /// assert_eq!(decoded.1[1], (20, format!("b")));
+    /// ```
+    pub fn as_mut(&mut self) -> AbomonatedMut<'_, T> {
+        let result: &mut T = unsafe { &mut *self.pointer };
+        AbomonatedMut { inner: result }
+    }
+}
+
+impl<T, S: DerefMut<Target=[u8]>> Abomonated<Vec<T>, S> {
+
+    /// Splits decoded vector data into one handle per element.
+    ///
+    /// The owned bytes are moved into a reference-counted `Shared<S>`, and each
+    /// handle retains a reference to them. The bytes are dropped once the last
+    /// handle is dropped. Handles may be sent to other threads when `T: Sync`.
+    ///
+    /// The requirement of `new` on clones of decoded `Rc` and `Arc` values
+    /// extends to the handles: such clones must not be used once the last
+    /// handle has been dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use abomonation::encode;
+    /// use abomonation::abomonated::Abomonated;
+    ///
+    /// let vector = (0..4u64).map(|i| (i, format!("{}", i)))
+    ///                       .collect::<Vec<_>>();
+    ///
+    /// let mut bytes = Vec::new();
+    /// unsafe { encode(&vector, &mut bytes).unwrap(); }
+    ///
+    /// let decoded = unsafe { Abomonated::<Vec<(u64, String)>,_>::new(bytes) }.unwrap();
+    /// let handles = decoded.split_elements()
+    ///                      .into_iter()
+    ///                      .map(|element| std::thread::spawn(move || element.0 + element.1.len() as u64))
+    ///                      .collect::<Vec<_>>();
+    ///
+    /// let total: u64 = handles.into_iter().map(|h| h.join().unwrap()).sum();
+    /// assert_eq!(total, 0 + 1 + 2 + 3 + 4);
+    /// ```
+    pub fn split_elements(self) -> Vec<Projected<T, Shared<S>>> {
+        let pointers = self.iter().map(|element| element as *const T).collect::<Vec<_>>();
+        let shared = Shared::new(self.decoded);
+        pointers
+            .into_iter()
+            .map(|pointer| Projected { pointer, decoded: shared.clone() })
+            .collect()
+    }
 }
 
 
@@ -121,7 +345,301 @@ impl<T, S: DerefMut<Target=[u8]>> Deref for Abomonated<T, S> {
     type Target = T;
     #[inline]
     fn deref(&self) -> &T {
-        let result: &T = unsafe { transmute(self.decoded.get_unchecked(0)) };
+        let result: &T = unsafe { &*self.pointer };
         result
     }
 }
+
+// `pointer` refers into `decoded`, so this is as if `Abomonated` held a `T` and an `S`.
+unsafe impl<T: Send, S: DerefMut<Target=[u8]> + Send> Send for Abomonated<T, S> { }
+unsafe impl<T: Sync, S: DerefMut<Target=[u8]> + Sync> Sync for Abomonated<T, S> { }
+
+/// A reference into decoded abomonated data, which owns the backing bytes.
+///
+/// A `Projected<U, S>` is produced by `Abomonated::map`, and dereferences to
+/// some `U` reachable from the decoded root. The bytes `S` are retained, so
+/// the reference stays valid for as long as the `Projected` exists.
+///
+/// # Safety
+///
+/// As with `Abomonated`, the type `S` must have its bytes at a fixed location,
+/// and these bytes must not be mutated. `Abomonated::new` already requires this
+/// of its caller, and `Projected` can only be constructed from an `Abomonated`.
+pub struct Projected<U: ?Sized, S> {
+    pointer: *const U,
+    decoded: S,
+}
+
+impl<U: ?Sized, S: Deref<Target=[u8]>> Projected<U, S> {
+    /// The owned bytes, as decoded.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.decoded
+    }
+}
+
+impl<U: ?Sized, S> Projected<U, S> {
+    /// Further projects the referenced data, retaining the owned bytes.
+    pub fn map<V: ?Sized, F>(self, logic: F) -> Projected<V, S>
+    where
+        F: for<'a> FnOnce(&'a U) -> &'a V,
+    {
+        let pointer: *const V = logic(self.deref());
+        Projected {
+            pointer,
+            decoded: self.decoded,
+        }
+    }
+}
+
+impl<U: ?Sized, S> Deref for Projected<U, S> {
+    type Target = U;
+    #[inline]
+    fn deref(&self) -> &U {
+        unsafe { &*self.pointer }
+    }
+}
+
+// Clones share the same bytes, and so the same referent.
+impl<U: ?Sized, S> Clone for Projected<U, Shared<S>> {
+    fn clone(&self) -> Self {
+        Projected {
+            pointer: self.pointer,
+            decoded: self.decoded.clone(),
+        }
+    }
+}
+
+// `Projected` hands out `&U` wherever it is, and owns an `S`.
+unsafe impl<U: ?Sized + Sync, S: Send> Send for Projected<U, S> { }
+unsafe impl<U: ?Sized + Sync, S: Sync> Sync for Projected<U, S> { }
+
+/// Reference-counted owned bytes, shared by several handles.
+///
+/// Cloning a `Shared<S>` does not copy the bytes, and so the location of the
+/// bytes is the same for all clones. It intentionally does not implement
+/// `DerefMut`, as other handles may be reading the decoded data.
+pub struct Shared<S> {
+    bytes: Arc<S>,
+}
+
+impl<S> Shared<S> {
+    /// Moves `bytes` into reference-counted storage.
+    pub fn new(bytes: S) -> Self {
+        Shared { bytes: Arc::new(bytes) }
+    }
+}
+
+impl<S> Clone for Shared<S> {
+    fn clone(&self) -> Self {
+        Shared { bytes: self.bytes.clone() }
+    }
+}
+
+impl<S: Deref<Target=[u8]>> Deref for Shared<S> {
+    type Target = [u8];
+    #[inline]
+    fn deref(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+/// Zero-initialized owned bytes with a specified alignment.
+///
+/// A `Vec<u8>` only guarantees an alignment of one, whereas decoding a `T`
+/// wants its first byte aligned to `mem::align_of::<T>()`. The bytes are
+/// heap allocated, and do not move when an `AlignedBytes` is moved.
+pub struct AlignedBytes {
+    pointer: NonNull<u8>,
+    layout: Layout,
+}
+
+impl AlignedBytes {
+    /// Allocates `length` zeroed bytes aligned to `align`.
+    ///
+    /// #Panics
+    ///
+    /// This method panics if `align` is not a power of two, or if the rounded
+    /// up length overflows `isize`.
+    pub fn new(length: usize, align: usize) -> Self {
+        let layout = Layout::from_size_align(length, align).expect("invalid layout");
+        let pointer = if layout.size() == 0 {
+            // zero-sized allocations are not allowed; use a dangling aligned pointer.
+            unsafe { NonNull::new_unchecked(layout.align() as *mut u8) }
+        }
+        else {
+            let pointer = unsafe { alloc::alloc_zeroed(layout) };
+            NonNull::new(pointer).unwrap_or_else(|| alloc::handle_alloc_error(layout))
+        };
+        AlignedBytes { pointer, layout }
+    }
+}
+
+impl Deref for AlignedBytes {
+    type Target = [u8];
+    #[inline]
+    fn deref(&self) -> &[u8] {
+        unsafe { std::slice::from_raw_parts(self.pointer.as_ptr(), self.layout.size()) }
+    }
+}
+
+impl DerefMut for AlignedBytes {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut [u8] {
+        unsafe { std::slice::from_raw_parts_mut(self.pointer.as_ptr(), self.layout.size()) }
+    }
+}
+
+impl Drop for AlignedBytes {
+    fn drop(&mut self) {
+        if self.layout.size() > 0 {
+            unsafe { alloc::dealloc(self.pointer.as_ptr(), self.layout); }
+        }
+    }
+}
+
+// `AlignedBytes` uniquely owns its allocation, like a `Box<[u8]>`.
+unsafe impl Send for AlignedBytes { }
+unsafe impl Sync for AlignedBytes { }
+
+/// Mutable access to decoded data, restricted to in-place mutation.
+///
+/// Decoded `Vec`, `String` and `Box` values point into the owned bytes, and
+/// must never be dropped, grown or replaced; a `&mut T` would allow all of
+/// these (e.g. through `mem::replace`). An `AbomonatedMut<'a, T>` instead
+/// dereferences mutably only when `T: Copy`, as such types cannot own memory.
+/// Other types offer structural access to their parts: the elements of
+/// vectors, slices and arrays, the contents of options and boxes, and the
+/// fields of tuples.
+pub struct AbomonatedMut<'a, T: ?Sized + 'a> {
+    inner: &'a mut T,
+}
+
+impl<'a, T: ?Sized + 'a> AbomonatedMut<'a, T> {
+    /// Reborrows the handle for a shorter lifetime.
+    pub fn reborrow(&mut self) -> AbomonatedMut<'_, T> {
+        AbomonatedMut { inner: self.inner }
+    }
+
+    /// Projects the handle to a part of the referenced data.
+    ///
+    /// # Safety
+    ///
+    /// The closure must not move, replace or drop any value which may be backed
+    /// by the owned bytes, and must return a reference to a part of its argument.
+    /// This is intended to expose the fields of types implementing `Abomonation`
+    /// through `unsafe_abomonate!`.
+    pub unsafe fn map_unchecked<U: ?Sized, F>(self, logic: F) -> AbomonatedMut<'a, U>
+    where
+        F: FnOnce(&'a mut T) -> &'a mut U,
+    {
+        AbomonatedMut { inner: logic(self.inner) }
+    }
+}
+
+impl<'a, T: ?Sized + 'a> Deref for AbomonatedMut<'a, T> {
+    type Target = T;
+    #[inline]
+    fn deref(&self) -> &T {
+        self.inner
+    }
+}
+
+impl<'a, T: Copy + 'a> DerefMut for AbomonatedMut<'a, T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        self.inner
+    }
+}
+
+impl<'a, T: 'a> AbomonatedMut<'a, [T]> {
+    /// Mutable access to the element at `index`, if it exists.
+    pub fn get_mut(&mut self, index: usize) -> Option<AbomonatedMut<'_, T>> {
+        self.inner.get_mut(index).map(|inner| AbomonatedMut { inner })
+    }
+    /// Mutable access to each element in turn.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item=AbomonatedMut<'_, T>> {
+        self.inner.iter_mut().map(|inner| AbomonatedMut { inner })
+    }
+    /// Converts the handle into mutable access to each element.
+    pub fn into_iter_mut(self) -> impl Iterator<Item=AbomonatedMut<'a, T>> {
+        self.inner.iter_mut().map(|inner| AbomonatedMut { inner })
+    }
+    /// The elements as a mutable slice, for types that cannot own memory.
+    pub fn as_mut_slice(&mut self) -> &mut [T] where T: Copy {
+        self.inner
+    }
+}
+
+impl<'a, T: 'a> AbomonatedMut<'a, Vec<T>> {
+    /// Mutable access to the existing elements; the length cannot change.
+    pub fn elements(self) -> AbomonatedMut<'a, [T]> {
+        AbomonatedMut { inner: &mut self.inner[..] }
+    }
+    /// Mutable access to the element at `index`, if it exists.
+    pub fn get_mut(&mut self, index: usize) -> Option<AbomonatedMut<'_, T>> {
+        self.inner.get_mut(index).map(|inner| AbomonatedMut { inner })
+    }
+    /// Mutable access to each element in turn.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item=AbomonatedMut<'_, T>> {
+        self.inner.iter_mut().map(|inner| AbomonatedMut { inner })
+    }
+    /// The elements as a mutable slice, for types that cannot own memory.
+    pub fn as_mut_slice(&mut self) -> &mut [T] where T: Copy {
+        &mut self.inner[..]
+    }
+}
+
+impl<'a, T: 'a, const N: usize> AbomonatedMut<'a, [T; N]> {
+    /// Mutable access to the elements.
+    pub fn elements(self) -> AbomonatedMut<'a, [T]> {
+        AbomonatedMut { inner: &mut self.inner[..] }
+    }
+}
+
+impl<'a> AbomonatedMut<'a, String> {
+    /// The string contents, which may be mutated but not resized.
+    pub fn as_mut_str(&mut self) -> &mut str {
+        self.inner.as_mut_str()
+    }
+}
+
+impl<'a, T: 'a> AbomonatedMut<'a, Option<T>> {
+    /// Mutable access to the contents, if any; the variant cannot change.
+    pub fn as_mut(self) -> Option<AbomonatedMut<'a, T>> {
+        self.inner.as_mut().map(|inner| AbomonatedMut { inner })
+    }
+}
+
+impl<'a, T: 'a> AbomonatedMut<'a, Box<T>> {
+    /// Mutable access to the boxed value.
+    pub fn contents(self) -> AbomonatedMut<'a, T> {
+        AbomonatedMut { inner: &mut **self.inner }
+    }
+}
+
+// general code for tuples, splitting a handle into handles for each field.
+macro_rules! tuple_fields {
+    ( $($name:ident)+) => (
+        impl<'a, $($name: 'a),*> AbomonatedMut<'a, ($($name,)*)> {
+            /// Splits the handle into mutable access to each field.
+            #[allow(non_snake_case)]
+            pub fn fields(self) -> ($(AbomonatedMut<'a, $name>,)*) {
+                let ($(ref mut $name,)*) = *self.inner;
+                ($(AbomonatedMut { inner: $name },)*)
+            }
+        }
+    );
+}
+
+tuple_fields!(A);
+tuple_fields!(A B);
+tuple_fields!(A B C);
+tuple_fields!(A B C D);
+tuple_fields!(A B C D E);
+tuple_fields!(A B C D E F);
+tuple_fields!(A B C D E F G);
+tuple_fields!(A B C D E F G H);
+tuple_fields!(A B C D E F G H I);
+tuple_fields!(A B C D E F G H I J);
+tuple_fields!(A B C D E F G H I J K);
+tuple_fields!(A B C D E F G H I J K L);
@@ -0,0 +1,90 @@
+//! Encoding within a budget of bytes.
+//!
+//! A writer with a cap on the size of its output, such as a message frame, would otherwise
+//! only fail once `encode` has written up to the cap. `encode_bounded` instead measures the
+//! value first, and fails with `EncodeError::LimitExceeded` without writing anything if it
+//! exceeds the budget.
+//!
+//! # Examples
+//! ```
+//! use abomonation::{encode_bounded, EncodeError};
+//!
+//! let vector = vec![format!("grawwwwrr!"); 1024];
+//!
+//! let mut bytes = Vec::new();
+//! match unsafe { encode_bounded(&vector, &mut bytes, 1 << 10) } {
+//!     Err(EncodeError::LimitExceeded { size, max_bytes }) => assert!(size > max_bytes),
+//!     _ => panic!("encoded beyond the budget"),
+//! }
+//! assert!(bytes.is_empty());
+//!
+//! unsafe { encode_bounded(&vector, &mut bytes, 1 << 16).unwrap(); }
+//! assert!(bytes.len() <= 1 << 16);
+//! ```
+
+use std::io::{Error, ErrorKind, Write};
+use std::io::Result as IOResult;
+use std::mem;
+
+use {Abomonation, encode, rc};
+use error::EncodeError;
+
+/// Encodes a value into a binary buffer, if it encodes to at most `max_bytes` bytes.
+///
+/// The value is measured before anything is written, and a value that is too large fails with
+/// `EncodeError::LimitExceeded`. Otherwise it is encoded as by `encode`, except that writing
+/// fails with `EncodeError::ExtentMismatch` rather than exceed the measured size, should the
+/// value's `extent` be incorrect.
+///
+/// Only the final addition of the size of `T` to the value's `extent` is checked. The `extent`
+/// methods sum the extents of the parts of a value without checks, as they do for `measure`,
+/// and so the bound holds only when those sums do not overflow. A value whose parts report more
+/// than `usize::MAX` bytes in all (which no value owns, but which incorrect `extent` methods or
+/// many borrowed `Cow` values of one static may report) panics in debug builds, and in release
+/// builds may be measured as within the budget. It then still writes no more than `max_bytes`
+/// bytes, but fails with `EncodeError::ExtentMismatch` after writing some of them, rather than
+/// with `EncodeError::LimitExceeded` before writing any.
+///
+/// # Safety
+///
+/// This method is unsafe for the same reasons as `encode`.
+pub unsafe fn encode_bounded<T: Abomonation, W: Write>(typed: &T, write: &mut W, max_bytes: usize) -> Result<(), EncodeError> {
+    let size = {
+        let _scope = rc::Scope::enter();
+        mem::size_of::<T>().checked_add(typed.extent()).ok_or(EncodeError::SizeOverflow)?
+    };
+    if size > max_bytes {
+        return Err(EncodeError::LimitExceeded { size, max_bytes });
+    }
+    let mut budget = Budget { write, remaining: size, exceeded: false };
+    let result = encode(typed, &mut budget);
+    if budget.exceeded || (result.is_ok() && budget.remaining > 0) {
+        return Err(EncodeError::ExtentMismatch { type_name: std::any::type_name::<T>() });
+    }
+    Ok(result?)
+}
+
+/// A writer accepting at most `remaining` more bytes.
+struct Budget<'a, W: Write + 'a> {
+    write: &'a mut W,
+    remaining: usize,
+    /// Set once a write would have exceeded `remaining`.
+    exceeded: bool,
+}
+
+impl<'a, W: Write> Write for Budget<'a, W> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> IOResult<usize> {
+        if buf.len() > self.remaining {
+            self.exceeded = true;
+            return Err(Error::new(ErrorKind::InvalidData, "entombed bytes exceed the measured extent"));
+        }
+        let written = self.write.write(buf)?;
+        self.remaining -= written;
+        Ok(written)
+    }
+    #[inline]
+    fn flush(&mut self) -> IOResult<()> {
+        self.write.flush()
+    }
+}
@@ -0,0 +1,190 @@
+//! Encoding a `Vec<T>` from elements supplied one at a time.
+//!
+//! Building a `Vec<T>` only to encode it holds every element in memory at once. A `VecEncoder`
+//! instead accepts its elements one at a time, so that they may be produced and dropped as they
+//! are encoded. As `encode` does, it writes the binary representation of every element before
+//! the data any of them own, and so accepts the elements twice: once for their representations,
+//! and once more, in the same order, for their owned data. The output is written in order, and
+//! so may be streamed to a pipe or socket. `encode_iter` encodes the elements of an iterator this
+//! way, traversing a clone of it for the second pass.
+//!
+//! # Examples
+//! ```
+//! use abomonation::decode;
+//! use abomonation::encoder::VecEncoder;
+//!
+//! let record = |i: u64| (i, format!("{}", i));
+//!
+//! let mut encoder = VecEncoder::<(u64, String), _>::new(256, Vec::new()).unwrap();
+//! for i in 0 .. 256u64 {
+//!     unsafe { encoder.push(&record(i)).unwrap(); }
+//! }
+//! for i in 0 .. 256u64 {
+//!     unsafe { encoder.push_owned(&record(i)).unwrap(); }
+//! }
+//! let mut bytes = encoder.finish().unwrap();
+//!
+//! if let Some((result, remaining)) = unsafe { decode::<Vec<(u64, String)>>(&mut bytes) } {
+//!     assert_eq!(result[17], (17, format!("17")));
+//!     assert!(remaining.len() == 0);
+//! }
+//! ```
+
+use std::any::type_name;
+use std::borrow::Borrow;
+use std::io::Write;
+use std::marker::PhantomData;
+
+use {Abomonation, rc};
+use error::EncodeError;
+use super::{typed_to_bytes, vec_header};
+
+/// Writes the header of a vector of `length` elements, returning the size of the elements.
+///
+/// This fails with `EncodeError::SizeOverflow` if no vector could hold `length` elements.
+fn write_header<T, W: Write>(length: usize, write: &mut W) -> Result<usize, EncodeError> {
+    let size = match length.checked_mul(std::mem::size_of::<T>()) {
+        Some(size) if size <= isize::MAX as usize => size,
+        _ => return Err(EncodeError::SizeOverflow),
+    };
+    write.write_all(unsafe { typed_to_bytes(std::slice::from_ref(&*vec_header::<T>(length))) })?;
+    Ok(size)
+}
+
+/// Encodes a `Vec<T>` of a declared length, from elements pushed one at a time.
+///
+/// The output decodes with `decode::<Vec<T>>`, and is identical to the output of `encode` for
+/// the vector of the same elements. Shared pointers are deduplicated across all elements, as
+/// they are within a single call to `encode`.
+///
+/// Each element is first passed to `push`, which writes its binary representation following
+/// the header. Once all elements have been pushed, each is passed again to `push_owned`, which
+/// writes the data it owns. Elements of types without indirection own no data, and need not
+/// be passed to `push_owned`. No element is held by the encoder, and the writer is never asked
+/// to seek.
+///
+/// An encoder dropped without calling `finish` leaves incomplete output.
+pub struct VecEncoder<T: Abomonation, W: Write> {
+    write: W,
+    length: usize,
+    /// The number of elements passed to `push`.
+    pushed: usize,
+    /// The number of elements passed to `push_owned`.
+    owned: usize,
+    /// Shared targets entombed by the elements passed to `push_owned`.
+    tables: rc::Tables,
+    phantom: PhantomData<T>,
+}
+
+impl<T: Abomonation, W: Write> VecEncoder<T, W> {
+    /// Writes the header of a vector of `length` elements, and returns an encoder for them.
+    ///
+    /// This fails with `EncodeError::SizeOverflow` if no vector could hold `length` elements.
+    pub fn new(length: usize, mut write: W) -> Result<Self, EncodeError> {
+        write_header::<T, _>(length, &mut write)?;
+        Ok(VecEncoder { write, length, pushed: 0, owned: 0, tables: rc::Tables::active(), phantom: PhantomData })
+    }
+
+    /// Writes the binary representation of the next element.
+    ///
+    /// This fails with `EncodeError::ExtentMismatch` if the declared number of elements have
+    /// already been pushed.
+    ///
+    /// # Safety
+    ///
+    /// This method is unsafe for the same reasons as `encode`.
+    #[inline]
+    pub unsafe fn push(&mut self, element: &T) -> Result<(), EncodeError> {
+        if self.pushed == self.length {
+            return Err(EncodeError::ExtentMismatch { type_name: type_name::<Vec<T>>() });
+        }
+        self.write.write_all(typed_to_bytes(std::slice::from_ref(element)))?;
+        self.pushed += 1;
+        Ok(())
+    }
+
+    /// Writes the data owned by the next element, once every element has been pushed.
+    ///
+    /// This fails with `EncodeError::ExtentMismatch` if fewer than the declared number of
+    /// elements have been pushed, or if the data of all of them have already been written.
+    ///
+    /// # Safety
+    ///
+    /// This method is unsafe for the same reasons as `encode`. The elements must be equal to
+    /// those passed to `push`, and passed in the same order, as otherwise the output does not
+    /// describe the data it holds, and decoding it is undefined behavior.
+    #[inline]
+    pub unsafe fn push_owned(&mut self, element: &T) -> Result<(), EncodeError> {
+        if self.pushed != self.length || self.owned == self.length {
+            return Err(EncodeError::ExtentMismatch { type_name: type_name::<Vec<T>>() });
+        }
+        if T::HAS_INDIRECTION {
+            let _scope = rc::Resumed::enter(&mut self.tables);
+            element.entomb(&mut self.write)?;
+        }
+        self.owned += 1;
+        Ok(())
+    }
+
+    /// The number of elements still to be pushed.
+    #[inline]
+    pub fn remaining(&self) -> usize {
+        self.length - self.pushed
+    }
+
+    /// Completes the encoding, and returns the writer.
+    ///
+    /// This fails with `EncodeError::ExtentMismatch` if fewer than the declared number of
+    /// elements were pushed, or, for types with indirection, passed to `push_owned`.
+    pub fn finish(self) -> Result<W, EncodeError> {
+        if self.pushed != self.length || (T::HAS_INDIRECTION && self.owned != self.length) {
+            return Err(EncodeError::ExtentMismatch { type_name: type_name::<Vec<T>>() });
+        }
+        Ok(self.write)
+    }
+}
+
+/// Encodes the elements of an iterator as a `Vec<T>`, without collecting them.
+///
+/// The elements are pushed to a `VecEncoder` as a clone of the iterator yields them, and then
+/// passed to `push_owned` as the iterator itself yields them, so that each element is produced
+/// twice. The output is identical to the output of `encode` for the vector of the same elements.
+/// This fails with `EncodeError::ExtentMismatch` if the iterator yields a different number of
+/// elements than its reported length.
+///
+/// # Safety
+///
+/// This method is unsafe for the same reasons as `encode`. The iterator and its clone must
+/// yield equal elements, as required by `VecEncoder::push_owned`.
+///
+/// # Examples
+/// ```
+/// use abomonation::{encode_iter, decode};
+///
+/// let mut bytes = Vec::new();
+/// unsafe { encode_iter::<String, _, _>((0 .. 256).map(|i| format!("{}", i)), &mut bytes).unwrap(); }
+///
+/// if let Some((result, remaining)) = unsafe { decode::<Vec<String>>(&mut bytes) } {
+///     assert_eq!(result.len(), 256);
+///     assert!(remaining.len() == 0);
+/// }
+/// ```
+pub unsafe fn encode_iter<T, I, W>(iter: I, write: &mut W) -> Result<(), EncodeError>
+where
+    T: Abomonation,
+    I: ExactSizeIterator + Clone,
+    I::Item: Borrow<T>,
+    W: Write,
+{
+    let mut encoder = VecEncoder::<T, _>::new(iter.len(), write)?;
+    for element in iter.clone() {
+        encoder.push(element.borrow())?;
+    }
+    if T::HAS_INDIRECTION {
+        for element in iter {
+            encoder.push_owned(element.borrow())?;
+        }
+    }
+    encoder.finish()?;
+    Ok(())
+}
@@ -0,0 +1,221 @@
+//! Encoding borrowed views as the owned types they stand for.
+//!
+//! Encoding data held as `&str`, `&[T]`, or `Option<&T>` with `encode` would first require
+//! building the owned `String`, `Vec<T>`, or `Option<T>`. The `Entomb<T>` trait instead lets a
+//! value be encoded as if it were a `T`, and `encode_as` writes bytes that `decode::<T>` reads
+//! back as the equivalent `T`.
+//!
+//! Implementations are provided structurally: `str` encodes as `String`; `[V]` and `Vec<V>` as
+//! `Vec<T>`; `Option<V>` as `Option<T>`; tuples component-wise; and `&V` as whatever `V`
+//! encodes as, in each case where `V: Entomb<T>`. Primitive types, `String`, and types using
+//! `#[derive(Entomb)]` or `unsafe_abomonate!` encode as themselves.
+//!
+//! # Examples
+//! ```
+//! use abomonation::{encode_as, decode};
+//!
+//! let names = ["grawwwwrr!", "moo", "oink"];
+//! let view = names.iter().enumerate().map(|(i, name)| (i as u64, *name)).collect::<Vec<_>>();
+//!
+//! // encode a Vec<(u64, &str)> as a Vec<(u64, String)>
+//! let mut bytes = Vec::new();
+//! unsafe { encode_as::<Vec<(u64, String)>, _, _>(&view, &mut bytes).unwrap(); }
+//!
+//! if let Some((result, remaining)) = unsafe { decode::<Vec<(u64, String)>>(&mut bytes) } {
+//!     assert_eq!(result[1], (1, "moo".to_owned()));
+//!     assert!(remaining.len() == 0);
+//! }
+//! ```
+
+use std::io::Write;
+use std::io::Result as IOResult;
+use std::mem::{self, ManuallyDrop};
+
+use Abomonation;
+use error::EncodeError;
+use super::{typed_to_bytes, vec_header};
+use rc;
+
+/// Values which can be encoded as if they were a `T`.
+///
+/// # Safety
+///
+/// The bytes of `stand_in`, followed by those written by `entomb_as`, must decode as a `T`
+/// equivalent to `self`. That is, `stand_in` must agree with such a `T` in every byte that
+/// `T::exhume` reads, and `entomb_as` must write what `T::entomb` would write.
+pub unsafe trait Entomb<T: Abomonation> {
+    /// A bitwise stand-in for the `T` this value encodes as.
+    ///
+    /// # Safety
+    ///
+    /// Only the bytes of the stand-in may be used. It must never be used as a `T` nor dropped,
+    /// as any pointers it holds need not be valid; `exhume` replaces them when decoding.
+    unsafe fn stand_in(&self) -> ManuallyDrop<T>;
+    /// Writes the data the `T` this value encodes as would write with `entomb`.
+    ///
+    /// # Safety
+    ///
+    /// This method is unsafe for the same reasons as `Abomonation::entomb`.
+    unsafe fn entomb_as<W: Write>(&self, write: &mut W) -> IOResult<()>;
+    /// Reports the number of bytes `entomb_as` would write.
+    fn extent_as(&self) -> usize;
+}
+
+/// Encodes a value into a binary buffer, as the `T` it stands for.
+///
+/// The written bytes decode with `decode::<T>` to a `T` equivalent to `view`. They need not
+/// equal the bytes `encode` would write for that `T`, as the values of pointers may differ.
+///
+/// # Safety
+///
+/// This method is unsafe for the same reasons as `encode`.
+#[inline]
+pub unsafe fn encode_as<T: Abomonation, V: ?Sized + Entomb<T>, W: Write>(view: &V, write: &mut W) -> Result<(), EncodeError> {
+    let _scope = rc::Scope::enter();
+    let stand_in = view.stand_in();
+    write.write_all(typed_to_bytes(std::slice::from_ref(&*stand_in)))?;
+    Ok(view.entomb_as(write)?)
+}
+
+/// Reports the number of bytes `encode_as` would write for `view`.
+#[inline]
+pub fn measure_as<T: Abomonation, V: ?Sized + Entomb<T>>(view: &V) -> usize {
+    let _scope = rc::Scope::enter();
+    mem::size_of::<T>() + view.extent_as()
+}
+
+/// Implements `Entomb<Self>` for types that implement `Abomonation`.
+///
+/// This is done for types with `unsafe_abomonate!`, as `#[derive(Entomb)]` does for derived
+/// types, and allows them to appear within views, for example as `&T` encoded as `T`.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! entomb_identity {
+    ($($t:ty),*) => {
+        $(
+            unsafe impl $crate::Entomb<$t> for $t {
+                #[inline] unsafe fn stand_in(&self) -> ::std::mem::ManuallyDrop<$t> {
+                    ::std::mem::ManuallyDrop::new(::std::ptr::read(self))
+                }
+                #[inline] unsafe fn entomb_as<W: ::std::io::Write>(&self, write: &mut W) -> ::std::io::Result<()> {
+                    $crate::Abomonation::entomb(self, write)
+                }
+                #[inline] fn extent_as(&self) -> usize {
+                    $crate::Abomonation::extent(self)
+                }
+            }
+        )*
+    };
+}
+
+entomb_identity!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+entomb_identity!(f32, f64, bool, char, (), String, ::std::time::Duration, ::time::Timestamp);
+
+unsafe impl<T: Abomonation, V: ?Sized + Entomb<T>> Entomb<T> for &V {
+    #[inline] unsafe fn stand_in(&self) -> ManuallyDrop<T> { (**self).stand_in() }
+    #[inline] unsafe fn entomb_as<W: Write>(&self, write: &mut W) -> IOResult<()> { (**self).entomb_as(write) }
+    #[inline] fn extent_as(&self) -> usize { (**self).extent_as() }
+}
+
+unsafe impl Entomb<String> for str {
+    #[inline]
+    unsafe fn stand_in(&self) -> ManuallyDrop<String> {
+        ManuallyDrop::new(String::from_raw_parts(self.as_ptr() as *mut u8, self.len(), self.len()))
+    }
+    #[inline]
+    unsafe fn entomb_as<W: Write>(&self, write: &mut W) -> IOResult<()> {
+        write.write_all(self.as_bytes())
+    }
+    #[inline]
+    fn extent_as(&self) -> usize {
+        self.len()
+    }
+}
+
+unsafe impl<T: Abomonation, V: Entomb<T>> Entomb<Vec<T>> for [V] {
+    #[inline]
+    unsafe fn stand_in(&self) -> ManuallyDrop<Vec<T>> {
+        vec_header(self.len())
+    }
+    #[inline]
+    unsafe fn entomb_as<W: Write>(&self, write: &mut W) -> IOResult<()> {
+        for element in self.iter() {
+            write.write_all(typed_to_bytes(std::slice::from_ref(&*element.stand_in())))?;
+        }
+        if T::HAS_INDIRECTION {
+            for element in self.iter() { element.entomb_as(write)?; }
+        }
+        Ok(())
+    }
+    #[inline]
+    fn extent_as(&self) -> usize {
+        let mut sum = mem::size_of::<T>() * self.len();
+        if T::HAS_INDIRECTION {
+            for element in self.iter() {
+                sum += element.extent_as();
+            }
+        }
+        sum
+    }
+}
+
+unsafe impl<T: Abomonation, V: Entomb<T>> Entomb<Vec<T>> for Vec<V> {
+    #[inline] unsafe fn stand_in(&self) -> ManuallyDrop<Vec<T>> { self[..].stand_in() }
+    #[inline] unsafe fn entomb_as<W: Write>(&self, write: &mut W) -> IOResult<()> { self[..].entomb_as(write) }
+    #[inline] fn extent_as(&self) -> usize { self[..].extent_as() }
+}
+
+unsafe impl<T: Abomonation, V: Entomb<T>> Entomb<Option<T>> for Option<V> {
+    #[inline]
+    unsafe fn stand_in(&self) -> ManuallyDrop<Option<T>> {
+        ManuallyDrop::new(self.as_ref().map(|inner| ManuallyDrop::into_inner(inner.stand_in())))
+    }
+    #[inline]
+    unsafe fn entomb_as<W: Write>(&self, write: &mut W) -> IOResult<()> {
+        if !T::HAS_INDIRECTION { return Ok(()); }
+        match *self {
+            Some(ref inner) => inner.entomb_as(write),
+            None => Ok(()),
+        }
+    }
+    #[inline]
+    fn extent_as(&self) -> usize {
+        if !T::HAS_INDIRECTION { return 0; }
+        self.as_ref().map(|inner| inner.extent_as()).unwrap_or(0)
+    }
+}
+
+// general code for tuples, pairing the component types with the types they encode as.
+macro_rules! tuple_entomb {
+    ( $($name:ident : $view:ident),+) => (
+        unsafe impl<$($name: Abomonation, $view: Entomb<$name>),*> Entomb<($($name,)*)> for ($($view,)*) {
+            #[allow(non_snake_case)]
+            #[inline] unsafe fn stand_in(&self) -> ManuallyDrop<($($name,)*)> {
+                let ($(ref $view,)*) = *self;
+                ManuallyDrop::new(($(ManuallyDrop::into_inner($view.stand_in()),)*))
+            }
+            #[allow(non_snake_case)]
+            #[inline] unsafe fn entomb_as<WRITE: Write>(&self, write: &mut WRITE) -> IOResult<()> {
+                let ($(ref $view,)*) = *self;
+                $($view.entomb_as(write)?;)*
+                Ok(())
+            }
+            #[allow(non_snake_case)]
+            #[inline] fn extent_as(&self) -> usize {
+                let mut size = 0;
+                let ($(ref $view,)*) = *self;
+                $( size += $view.extent_as(); )*
+                size
+            }
+        }
+    );
+}
+
+tuple_entomb!(A: VA);
+tuple_entomb!(A: VA, B: VB);
+tuple_entomb!(A: VA, B: VB, C: VC);
+tuple_entomb!(A: VA, B: VB, C: VC, D: VD);
+tuple_entomb!(A: VA, B: VB, C: VC, D: VD, E: VE);
+tuple_entomb!(A: VA, B: VB, C: VC, D: VD, E: VE, F: VF);
+tuple_entomb!(A: VA, B: VB, C: VC, D: VD, E: VE, F: VF, G: VG);
+tuple_entomb!(A: VA, B: VB, C: VC, D: VD, E: VE, F: VF, G: VG, H: VH);
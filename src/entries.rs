@@ -0,0 +1,35 @@
+//! Iteration over the entries of frozen and flat maps, which both store them in a slice.
+
+/// An iterator over the entries of a frozen or flat map, as `(&K, &V)` pairs.
+#[derive(Clone, Debug)]
+pub struct Iter<'a, K: 'a, V: 'a> {
+    entries: ::std::slice::Iter<'a, (K, V)>,
+}
+
+impl<'a, K, V> Iter<'a, K, V> {
+    #[inline]
+    pub(crate) fn new(entries: &'a [(K, V)]) -> Self {
+        Iter { entries: entries.iter() }
+    }
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.entries.next().map(|(key, val)| (key, val))
+    }
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.entries.size_hint()
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for Iter<'a, K, V> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.entries.next_back().map(|(key, val)| (key, val))
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for Iter<'a, K, V> { }
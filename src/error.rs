@@ -0,0 +1,59 @@
+//! Errors from encoding.
+//!
+//! `encode` returns an `io::Result`, which cannot distinguish a failing writer from a value
+//! that cannot be encoded as requested. The encoding helpers `encode_slice`, `encode_str`,
+//! `encode_pod`, `encode_pod_slice`, `encode_as`, `encode_iter`, `encode_bounded`, and
+//! `VecEncoder`, as well as `Abomonated::from_value`, instead return an `EncodeError`.
+
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+/// The reason encoding failed.
+#[derive(Debug)]
+pub enum EncodeError {
+    /// The writer failed.
+    Io(io::Error),
+    /// The size of the encoding overflowed a `usize`.
+    SizeOverflow,
+    /// A value wrote a different number of bytes or elements than it reported.
+    ///
+    /// This indicates an incorrect `extent`, or an iterator reporting an incorrect length.
+    ExtentMismatch {
+        /// The name of the type whose encoding disagreed with its report.
+        type_name: &'static str,
+    },
+    /// The encoding would exceed a budget of bytes.
+    LimitExceeded {
+        /// The number of bytes the value encodes to.
+        size: usize,
+        /// The budget it exceeded.
+        max_bytes: usize,
+    },
+}
+
+impl fmt::Display for EncodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            EncodeError::Io(ref error) => write!(f, "writer failed: {}", error),
+            EncodeError::SizeOverflow => f.write_str("size of encoding overflowed"),
+            EncodeError::ExtentMismatch { type_name } => write!(f, "encoding of {} disagreed with its reported extent", type_name),
+            EncodeError::LimitExceeded { size, max_bytes } => write!(f, "encoding of {} bytes exceeds the budget of {} bytes", size, max_bytes),
+        }
+    }
+}
+
+impl Error for EncodeError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            EncodeError::Io(ref error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for EncodeError {
+    fn from(error: io::Error) -> Self {
+        EncodeError::Io(error)
+    }
+}
@@ -0,0 +1,349 @@
+//! Ordered maps and sets backed by sorted vectors.
+//!
+//! `FlatMap` and `FlatSet` keep their contents in a single `Vec` sorted by key, and so encode
+//! and decode with the same machinery as `Vec`. Unlike the frozen maps they may be modified,
+//! though each single insertion or removal moves the elements after it; large batches should
+//! be added with `extend`, which sorts the batch and merges it in one pass.
+//!
+//! # Examples
+//! ```
+//! use abomonation::{encode, decode};
+//! use abomonation::flat::FlatMap;
+//!
+//! let mut index = (0 .. 256u64).map(|i| (i * 2, format!("{}", i))).collect::<FlatMap<_,_>>();
+//! index.extend((0 .. 256u64).map(|i| (i * 2 + 1, format!("{}!", i))));
+//!
+//! let mut bytes = Vec::new();
+//! unsafe { encode(&index, &mut bytes).unwrap(); }
+//!
+//! if let Some((result, remaining)) = unsafe { decode::<FlatMap<u64, String>>(&mut bytes) } {
+//!     assert_eq!(result.get(&7).map(|s| s.as_str()), Some("3!"));
+//!     assert_eq!(result.len(), 512);
+//!     assert!(remaining.len() == 0);
+//! }
+//! ```
+
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::io::Write;
+use std::io::Result as IOResult;
+use std::iter::FromIterator;
+
+use Abomonation;
+
+pub use entries::Iter;
+
+/// An ordered map backed by a vector of entries sorted by key.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FlatMap<K, V> {
+    /// Entries in strictly increasing order of key.
+    entries: Vec<(K, V)>,
+}
+
+impl<K: Ord, V> FlatMap<K, V> {
+    /// Creates an empty map.
+    pub fn new() -> Self {
+        FlatMap { entries: Vec::new() }
+    }
+
+    /// Creates an empty map with space for `capacity` entries.
+    pub fn with_capacity(capacity: usize) -> Self {
+        FlatMap { entries: Vec::with_capacity(capacity) }
+    }
+
+    /// Returns a reference to the value for `key`, if present.
+    #[inline]
+    pub fn get<Q: ?Sized + Ord>(&self, key: &Q) -> Option<&V> where K: Borrow<Q> {
+        self.search(key).ok().map(|index| &self.entries[index].1)
+    }
+
+    /// Returns a mutable reference to the value for `key`, if present.
+    #[inline]
+    pub fn get_mut<Q: ?Sized + Ord>(&mut self, key: &Q) -> Option<&mut V> where K: Borrow<Q> {
+        match self.search(key) {
+            Ok(index) => Some(&mut self.entries[index].1),
+            Err(_) => None,
+        }
+    }
+
+    /// Indicates whether the map contains `key`.
+    #[inline]
+    pub fn contains_key<Q: ?Sized + Ord>(&self, key: &Q) -> bool where K: Borrow<Q> {
+        self.search(key).is_ok()
+    }
+
+    /// Inserts a value for `key`, returning the previous value if any.
+    ///
+    /// As with `BTreeMap`, a key already present is kept, and only its value is replaced.
+    pub fn insert(&mut self, key: K, val: V) -> Option<V> {
+        match self.search(&key) {
+            Ok(index) => Some(::std::mem::replace(&mut self.entries[index].1, val)),
+            Err(index) => { self.entries.insert(index, (key, val)); None },
+        }
+    }
+
+    /// Removes the entry for `key`, returning its value if present.
+    pub fn remove<Q: ?Sized + Ord>(&mut self, key: &Q) -> Option<V> where K: Borrow<Q> {
+        match self.search(key) {
+            Ok(index) => Some(self.entries.remove(index).1),
+            Err(_) => None,
+        }
+    }
+
+    /// Locates `key` among the entries, or the position at which it would be inserted.
+    #[inline]
+    fn search<Q: ?Sized + Ord>(&self, key: &Q) -> Result<usize, usize> where K: Borrow<Q> {
+        self.entries.binary_search_by(|(k, _)| k.borrow().cmp(key))
+    }
+}
+
+impl<K, V> FlatMap<K, V> {
+    /// The number of entries in the map.
+    #[inline] pub fn len(&self) -> usize { self.entries.len() }
+    /// Indicates whether the map has no entries.
+    #[inline] pub fn is_empty(&self) -> bool { self.entries.is_empty() }
+    /// Removes all entries, retaining the allocation.
+    #[inline] pub fn clear(&mut self) { self.entries.clear() }
+    /// The entries of the map, in order of key.
+    #[inline] pub fn as_slice(&self) -> &[(K, V)] { &self.entries[..] }
+    /// Iterates over the entries of the map, in order of key.
+    #[inline] pub fn iter(&self) -> Iter<'_, K, V> { Iter::new(&self.entries) }
+    /// Iterates over the keys of the map, in order.
+    #[inline] pub fn keys(&self) -> impl DoubleEndedIterator<Item=&K> + ExactSizeIterator { self.entries.iter().map(|(key, _)| key) }
+    /// Iterates over the values of the map, in order of key.
+    #[inline] pub fn values(&self) -> impl DoubleEndedIterator<Item=&V> + ExactSizeIterator { self.entries.iter().map(|(_, val)| val) }
+    /// The sorted entries of the map.
+    #[inline] pub fn into_vec(self) -> Vec<(K, V)> { self.entries }
+}
+
+impl<K: Ord, V> Default for FlatMap<K, V> {
+    fn default() -> Self { Self::new() }
+}
+
+impl<K: Ord, V> Extend<(K, V)> for FlatMap<K, V> {
+    /// Inserts the entries as `insert` would, keeping the first of equal keys and the last of
+    /// their values.
+    ///
+    /// The new entries are sorted and then merged with the existing entries in a single pass.
+    fn extend<I: IntoIterator<Item=(K, V)>>(&mut self, iter: I) {
+        let batch = sorted(iter.into_iter().collect(), |x, y| x.0.cmp(&y.0), replace_value);
+        let entries = ::std::mem::take(&mut self.entries);
+        self.entries = merge(entries, batch, |x, y| x.0.cmp(&y.0), replace_value);
+    }
+}
+
+impl<K: Ord, V> FromIterator<(K, V)> for FlatMap<K, V> {
+    /// Collects the entries as `insert` would, keeping the first of equal keys and the last of
+    /// their values.
+    fn from_iter<I: IntoIterator<Item=(K, V)>>(iter: I) -> Self {
+        FlatMap { entries: sorted(iter.into_iter().collect(), |x, y| x.0.cmp(&y.0), replace_value) }
+    }
+}
+
+impl<K, V> IntoIterator for FlatMap<K, V> {
+    type Item = (K, V);
+    type IntoIter = ::std::vec::IntoIter<(K, V)>;
+    fn into_iter(self) -> Self::IntoIter { self.entries.into_iter() }
+}
+
+impl<'a, K, V> IntoIterator for &'a FlatMap<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+    fn into_iter(self) -> Self::IntoIter { self.iter() }
+}
+
+impl<K: Abomonation, V: Abomonation> Abomonation for FlatMap<K, V> {
+    #[inline]
+    unsafe fn entomb<W: Write>(&self, write: &mut W) -> IOResult<()> {
+        self.entries.entomb(write)
+    }
+    #[inline]
+    unsafe fn exhume<'b>(&mut self, bytes: &'b mut [u8]) -> Option<&'b mut [u8]> {
+        self.entries.exhume(bytes)
+    }
+    #[inline]
+    fn extent(&self) -> usize {
+        self.entries.extent()
+    }
+}
+
+/// An ordered set backed by a sorted vector.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FlatSet<T> {
+    /// Elements in strictly increasing order.
+    elements: Vec<T>,
+}
+
+impl<T: Ord> FlatSet<T> {
+    /// Creates an empty set.
+    pub fn new() -> Self {
+        FlatSet { elements: Vec::new() }
+    }
+
+    /// Creates an empty set with space for `capacity` elements.
+    pub fn with_capacity(capacity: usize) -> Self {
+        FlatSet { elements: Vec::with_capacity(capacity) }
+    }
+
+    /// Indicates whether the set contains `value`.
+    #[inline]
+    pub fn contains<Q: ?Sized + Ord>(&self, value: &Q) -> bool where T: Borrow<Q> {
+        self.search(value).is_ok()
+    }
+
+    /// Returns the element equal to `value`, if present.
+    #[inline]
+    pub fn get<Q: ?Sized + Ord>(&self, value: &Q) -> Option<&T> where T: Borrow<Q> {
+        self.search(value).ok().map(|index| &self.elements[index])
+    }
+
+    /// Inserts `value`, returning `false` if an equal element was already present.
+    ///
+    /// As with `BTreeSet`, an equal element already present is kept, and `value` is dropped.
+    pub fn insert(&mut self, value: T) -> bool {
+        match self.search(&value) {
+            Ok(_) => false,
+            Err(index) => { self.elements.insert(index, value); true },
+        }
+    }
+
+    /// Removes the element equal to `value`, returning `false` if none was present.
+    pub fn remove<Q: ?Sized + Ord>(&mut self, value: &Q) -> bool where T: Borrow<Q> {
+        match self.search(value) {
+            Ok(index) => { self.elements.remove(index); true },
+            Err(_) => false,
+        }
+    }
+
+    /// Locates `value` among the elements, or the position at which it would be inserted.
+    #[inline]
+    fn search<Q: ?Sized + Ord>(&self, value: &Q) -> Result<usize, usize> where T: Borrow<Q> {
+        self.elements.binary_search_by(|element| element.borrow().cmp(value))
+    }
+}
+
+impl<T> FlatSet<T> {
+    /// The number of elements in the set.
+    #[inline] pub fn len(&self) -> usize { self.elements.len() }
+    /// Indicates whether the set has no elements.
+    #[inline] pub fn is_empty(&self) -> bool { self.elements.is_empty() }
+    /// Removes all elements, retaining the allocation.
+    #[inline] pub fn clear(&mut self) { self.elements.clear() }
+    /// The elements of the set, in order.
+    #[inline] pub fn as_slice(&self) -> &[T] { &self.elements[..] }
+    /// Iterates over the elements of the set, in order.
+    #[inline] pub fn iter(&self) -> ::std::slice::Iter<'_, T> { self.elements.iter() }
+    /// The sorted elements of the set.
+    #[inline] pub fn into_vec(self) -> Vec<T> { self.elements }
+}
+
+impl<T: Ord> Default for FlatSet<T> {
+    fn default() -> Self { Self::new() }
+}
+
+impl<T: Ord> Extend<T> for FlatSet<T> {
+    /// Inserts the elements as `insert` would, keeping the first of equal elements.
+    ///
+    /// The new elements are sorted and then merged with the existing elements in a single pass.
+    fn extend<I: IntoIterator<Item=T>>(&mut self, iter: I) {
+        let batch = sorted(iter.into_iter().collect(), Ord::cmp, keep);
+        let elements = ::std::mem::take(&mut self.elements);
+        self.elements = merge(elements, batch, Ord::cmp, keep);
+    }
+}
+
+impl<T: Ord> FromIterator<T> for FlatSet<T> {
+    /// Collects the elements as `insert` would, keeping the first of equal elements.
+    fn from_iter<I: IntoIterator<Item=T>>(iter: I) -> Self {
+        FlatSet { elements: sorted(iter.into_iter().collect(), Ord::cmp, keep) }
+    }
+}
+
+impl<T> IntoIterator for FlatSet<T> {
+    type Item = T;
+    type IntoIter = ::std::vec::IntoIter<T>;
+    fn into_iter(self) -> Self::IntoIter { self.elements.into_iter() }
+}
+
+impl<'a, T> IntoIterator for &'a FlatSet<T> {
+    type Item = &'a T;
+    type IntoIter = ::std::slice::Iter<'a, T>;
+    fn into_iter(self) -> Self::IntoIter { self.elements.iter() }
+}
+
+impl<T: Abomonation> Abomonation for FlatSet<T> {
+    #[inline]
+    unsafe fn entomb<W: Write>(&self, write: &mut W) -> IOResult<()> {
+        self.elements.entomb(write)
+    }
+    #[inline]
+    unsafe fn exhume<'b>(&mut self, bytes: &'b mut [u8]) -> Option<&'b mut [u8]> {
+        self.elements.exhume(bytes)
+    }
+    #[inline]
+    fn extent(&self) -> usize {
+        self.elements.extent()
+    }
+}
+
+/// Combines equal entries by keeping the earlier key and replacing its value with the later.
+#[inline]
+fn replace_value<K, V>(earlier: &mut (K, V), later: (K, V)) {
+    earlier.1 = later.1;
+}
+
+/// Combines equal elements by keeping the earlier, and dropping the later.
+#[inline]
+fn keep<T>(_earlier: &mut T, _later: T) { }
+
+/// Sorts `items` stably, and combines each run of equal items into its first, in order.
+fn sorted<T, F: Fn(&T, &T) -> Ordering, C: Fn(&mut T, T)>(mut items: Vec<T>, cmp: F, combine: C) -> Vec<T> {
+    items.sort_by(&cmp);
+    let mut result: Vec<T> = Vec::with_capacity(items.len());
+    for item in items {
+        if let Some(last) = result.last_mut() {
+            if cmp(last, &item) == Ordering::Equal {
+                combine(last, item);
+                continue;
+            }
+        }
+        result.push(item);
+    }
+    result
+}
+
+/// Merges two sorted and deduplicated vectors, combining items of `new` into equal items of `old`.
+fn merge<T, F: Fn(&T, &T) -> Ordering, C: Fn(&mut T, T)>(old: Vec<T>, new: Vec<T>, cmp: F, combine: C) -> Vec<T> {
+    // appending is common, and needs no merge.
+    let appends = match (old.last(), new.first()) {
+        (Some(last), Some(first)) => cmp(last, first) == Ordering::Less,
+        _ => true,
+    };
+    if appends {
+        let mut old = old;
+        old.extend(new);
+        return old;
+    }
+
+    let mut result = Vec::with_capacity(old.len() + new.len());
+    let mut old = old.into_iter().peekable();
+    let mut new = new.into_iter().peekable();
+    loop {
+        let order = match (old.peek(), new.peek()) {
+            (Some(x), Some(y)) => cmp(x, y),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => break,
+        };
+        match order {
+            Ordering::Less => result.extend(old.next()),
+            Ordering::Greater => result.extend(new.next()),
+            Ordering::Equal => {
+                let (mut earlier, later) = (old.next().unwrap(), new.next().unwrap());
+                combine(&mut earlier, later);
+                result.push(earlier);
+            },
+        }
+    }
+    result
+}
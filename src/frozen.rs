@@ -0,0 +1,288 @@
+//! Read-only maps whose encoded form supports lookup directly after `decode`.
+//!
+//! `HashMap` and `BTreeMap` cannot implement `Abomonation`, as their internal layouts cannot be
+//! rebuilt by correcting pointers. The frozen maps here store their entries in vectors instead,
+//! so that they decode like any other `Vec`, and a decoded `&FrozenHashMap` or `&FrozenBTreeMap`
+//! answers `get`, `range`, and iteration without copying or allocating.
+//!
+//! # Examples
+//! ```
+//! use std::collections::HashMap;
+//! use abomonation::{encode, decode};
+//! use abomonation::frozen::FrozenHashMap;
+//!
+//! let map = (0 .. 256u64).map(|i| (format!("{}", i), i)).collect::<HashMap<_,_>>();
+//! let frozen = FrozenHashMap::from(map);
+//!
+//! let mut bytes = Vec::new();
+//! unsafe { encode(&frozen, &mut bytes).unwrap(); }
+//!
+//! if let Some((result, remaining)) = unsafe { decode::<FrozenHashMap<String, u64>>(&mut bytes) } {
+//!     assert_eq!(result.get("17"), Some(&17));
+//!     assert_eq!(result.get("grawwwwrr!"), None);
+//!     assert!(remaining.len() == 0);
+//! }
+//! ```
+
+use std::borrow::Borrow;
+use std::collections::{BTreeMap, HashMap};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{BuildHasher, Hash, Hasher};
+use std::io::Write;
+use std::io::Result as IOResult;
+use std::iter::FromIterator;
+use std::ops::{Bound, RangeBounds};
+
+use Abomonation;
+
+pub use entries::Iter;
+
+/// A read-only hash map, which can be used in place after decoding.
+///
+/// Entries are grouped by bucket in a single vector, and each lookup hashes its key and
+/// searches one bucket. Keys are hashed with `DefaultHasher::new()`, whose output is fixed for
+/// a given Rust release but not across releases; as with all abomonated data, maps should be
+/// decoded by the same build that encoded them.
+#[derive(Clone, Debug)]
+pub struct FrozenHashMap<K, V> {
+    /// Bucket `b` holds `entries[offsets[b] .. offsets[b+1]]`; the number of buckets is a power of two.
+    offsets: Vec<usize>,
+    entries: Vec<(K, V)>,
+}
+
+impl<K: Hash + Eq, V> FrozenHashMap<K, V> {
+    /// Creates an empty map.
+    pub fn new() -> Self {
+        FrozenHashMap { offsets: vec![0, 0], entries: Vec::new() }
+    }
+
+    /// Returns a reference to the value for `key`, if present.
+    #[inline]
+    pub fn get<Q: ?Sized + Hash + Eq>(&self, key: &Q) -> Option<&V> where K: Borrow<Q> {
+        self.get_key_value(key).map(|(_, val)| val)
+    }
+
+    /// Returns the stored key and a reference to the value for `key`, if present.
+    #[inline]
+    pub fn get_key_value<Q: ?Sized + Hash + Eq>(&self, key: &Q) -> Option<(&K, &V)> where K: Borrow<Q> {
+        self.bucket(key)
+            .iter()
+            .find(|(k, _)| k.borrow() == key)
+            .map(|(key, val)| (key, val))
+    }
+
+    /// Indicates whether the map contains `key`.
+    #[inline]
+    pub fn contains_key<Q: ?Sized + Hash + Eq>(&self, key: &Q) -> bool where K: Borrow<Q> {
+        self.get_key_value(key).is_some()
+    }
+
+    /// The entries that may contain `key`.
+    ///
+    /// Offsets are read with checks, so that corrupt decoded data yields no entries rather than
+    /// a panic.
+    #[inline]
+    fn bucket<Q: ?Sized + Hash>(&self, key: &Q) -> &[(K, V)] {
+        let buckets = self.offsets.len().saturating_sub(1);
+        if buckets == 0 { return &[]; }
+        let bucket = (hash(key) as usize) & (buckets - 1);
+        match (self.offsets.get(bucket), self.offsets.get(bucket + 1)) {
+            (Some(&lower), Some(&upper)) => self.entries.get(lower .. upper).unwrap_or(&[]),
+            _ => &[],
+        }
+    }
+}
+
+impl<K, V> FrozenHashMap<K, V> {
+    /// The number of entries in the map.
+    #[inline] pub fn len(&self) -> usize { self.entries.len() }
+    /// Indicates whether the map has no entries.
+    #[inline] pub fn is_empty(&self) -> bool { self.entries.is_empty() }
+    /// Iterates over the entries of the map, in an unspecified order.
+    #[inline] pub fn iter(&self) -> Iter<'_, K, V> { Iter::new(&self.entries) }
+    /// Iterates over the keys of the map, in an unspecified order.
+    #[inline] pub fn keys(&self) -> impl Iterator<Item=&K> { self.entries.iter().map(|(key, _)| key) }
+    /// Iterates over the values of the map, in an unspecified order.
+    #[inline] pub fn values(&self) -> impl Iterator<Item=&V> { self.entries.iter().map(|(_, val)| val) }
+}
+
+impl<K: Hash + Eq, V> Default for FrozenHashMap<K, V> {
+    fn default() -> Self { Self::new() }
+}
+
+impl<K: Hash + Eq, V, S: BuildHasher> From<HashMap<K, V, S>> for FrozenHashMap<K, V> {
+    fn from(map: HashMap<K, V, S>) -> Self {
+        let buckets = map.len().next_power_of_two();
+        let mut entries = map.into_iter().map(|(key, val)| ((hash(&key) as usize) & (buckets - 1), key, val)).collect::<Vec<_>>();
+        entries.sort_by_key(|&(bucket, _, _)| bucket);
+
+        let mut offsets = Vec::with_capacity(buckets + 1);
+        offsets.push(0);
+        let mut cursor = 0;
+        for bucket in 0 .. buckets {
+            while cursor < entries.len() && entries[cursor].0 == bucket { cursor += 1; }
+            offsets.push(cursor);
+        }
+
+        let entries = entries.into_iter().map(|(_, key, val)| (key, val)).collect();
+        FrozenHashMap { offsets, entries }
+    }
+}
+
+impl<K: Hash + Eq, V> FromIterator<(K, V)> for FrozenHashMap<K, V> {
+    /// Collects the entries as `HashMap` would, keeping the last value for repeated keys.
+    fn from_iter<I: IntoIterator<Item=(K, V)>>(iter: I) -> Self {
+        iter.into_iter().collect::<HashMap<K, V>>().into()
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a FrozenHashMap<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+    fn into_iter(self) -> Self::IntoIter { self.iter() }
+}
+
+// Maps are equal when they hold the same entries, whichever order their buckets list them in.
+impl<K: Hash + Eq, V: PartialEq> PartialEq for FrozenHashMap<K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().all(|(key, val)| other.get(key) == Some(val))
+    }
+}
+
+impl<K: Hash + Eq, V: Eq> Eq for FrozenHashMap<K, V> { }
+
+impl<K: Abomonation, V: Abomonation> Abomonation for FrozenHashMap<K, V> {
+    #[inline]
+    unsafe fn entomb<W: Write>(&self, write: &mut W) -> IOResult<()> {
+        self.offsets.entomb(write)?;
+        self.entries.entomb(write)
+    }
+    #[inline]
+    unsafe fn exhume<'b>(&mut self, bytes: &'b mut [u8]) -> Option<&'b mut [u8]> {
+        let bytes = self.offsets.exhume(bytes)?;
+        self.entries.exhume(bytes)
+    }
+    #[inline]
+    fn extent(&self) -> usize {
+        self.offsets.extent() + self.entries.extent()
+    }
+}
+
+/// A read-only ordered map, which can be used in place after decoding.
+///
+/// Entries are held in a single vector sorted by key, and lookups use binary search.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FrozenBTreeMap<K, V> {
+    /// Entries in strictly increasing order of key.
+    entries: Vec<(K, V)>,
+}
+
+impl<K: Ord, V> FrozenBTreeMap<K, V> {
+    /// Creates an empty map.
+    pub fn new() -> Self {
+        FrozenBTreeMap { entries: Vec::new() }
+    }
+
+    /// Returns a reference to the value for `key`, if present.
+    #[inline]
+    pub fn get<Q: ?Sized + Ord>(&self, key: &Q) -> Option<&V> where K: Borrow<Q> {
+        self.get_key_value(key).map(|(_, val)| val)
+    }
+
+    /// Returns the stored key and a reference to the value for `key`, if present.
+    #[inline]
+    pub fn get_key_value<Q: ?Sized + Ord>(&self, key: &Q) -> Option<(&K, &V)> where K: Borrow<Q> {
+        self.entries
+            .binary_search_by(|(k, _)| k.borrow().cmp(key))
+            .ok()
+            .map(|index| (&self.entries[index].0, &self.entries[index].1))
+    }
+
+    /// Indicates whether the map contains `key`.
+    #[inline]
+    pub fn contains_key<Q: ?Sized + Ord>(&self, key: &Q) -> bool where K: Borrow<Q> {
+        self.get_key_value(key).is_some()
+    }
+
+    /// Iterates over the entries whose keys lie in `range`, in order of key.
+    ///
+    /// Unlike `BTreeMap::range`, this does not panic if the start of `range` exceeds its end,
+    /// and instead yields no entries.
+    pub fn range<Q: ?Sized + Ord, R: RangeBounds<Q>>(&self, range: R) -> Iter<'_, K, V> where K: Borrow<Q> {
+        let lower = match range.start_bound() {
+            Bound::Included(start) => self.entries.partition_point(|(k, _)| k.borrow() < start),
+            Bound::Excluded(start) => self.entries.partition_point(|(k, _)| k.borrow() <= start),
+            Bound::Unbounded => 0,
+        };
+        let upper = match range.end_bound() {
+            Bound::Included(end) => self.entries.partition_point(|(k, _)| k.borrow() <= end),
+            Bound::Excluded(end) => self.entries.partition_point(|(k, _)| k.borrow() < end),
+            Bound::Unbounded => self.entries.len(),
+        };
+        let entries = if lower < upper { &self.entries[lower .. upper] } else { &[] };
+        Iter::new(entries)
+    }
+}
+
+impl<K, V> FrozenBTreeMap<K, V> {
+    /// The number of entries in the map.
+    #[inline] pub fn len(&self) -> usize { self.entries.len() }
+    /// Indicates whether the map has no entries.
+    #[inline] pub fn is_empty(&self) -> bool { self.entries.is_empty() }
+    /// Iterates over the entries of the map, in order of key.
+    #[inline] pub fn iter(&self) -> Iter<'_, K, V> { Iter::new(&self.entries) }
+    /// Iterates over the keys of the map, in order.
+    #[inline] pub fn keys(&self) -> impl Iterator<Item=&K> { self.entries.iter().map(|(key, _)| key) }
+    /// Iterates over the values of the map, in order of key.
+    #[inline] pub fn values(&self) -> impl Iterator<Item=&V> { self.entries.iter().map(|(_, val)| val) }
+    /// The entry with the least key, if any.
+    #[inline] pub fn first_key_value(&self) -> Option<(&K, &V)> { self.entries.first().map(|(key, val)| (key, val)) }
+    /// The entry with the greatest key, if any.
+    #[inline] pub fn last_key_value(&self) -> Option<(&K, &V)> { self.entries.last().map(|(key, val)| (key, val)) }
+}
+
+impl<K: Ord, V> Default for FrozenBTreeMap<K, V> {
+    fn default() -> Self { Self::new() }
+}
+
+impl<K: Ord, V> From<BTreeMap<K, V>> for FrozenBTreeMap<K, V> {
+    fn from(map: BTreeMap<K, V>) -> Self {
+        FrozenBTreeMap { entries: map.into_iter().collect() }
+    }
+}
+
+impl<K: Ord, V> FromIterator<(K, V)> for FrozenBTreeMap<K, V> {
+    /// Collects the entries as `BTreeMap` would, keeping the last value for repeated keys.
+    fn from_iter<I: IntoIterator<Item=(K, V)>>(iter: I) -> Self {
+        iter.into_iter().collect::<BTreeMap<K, V>>().into()
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a FrozenBTreeMap<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+    fn into_iter(self) -> Self::IntoIter { self.iter() }
+}
+
+impl<K: Abomonation, V: Abomonation> Abomonation for FrozenBTreeMap<K, V> {
+    #[inline]
+    unsafe fn entomb<W: Write>(&self, write: &mut W) -> IOResult<()> {
+        self.entries.entomb(write)
+    }
+    #[inline]
+    unsafe fn exhume<'b>(&mut self, bytes: &'b mut [u8]) -> Option<&'b mut [u8]> {
+        self.entries.exhume(bytes)
+    }
+    #[inline]
+    fn extent(&self) -> usize {
+        self.entries.extent()
+    }
+}
+
+/// Hashes a key independently of any per-process random state.
+#[inline]
+fn hash<Q: ?Sized + Hash>(key: &Q) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    key.hash(&mut hasher);
+    hasher.finish()
+}
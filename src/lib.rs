@@ -8,7 +8,8 @@
 //!
 //! Abomonation does several unsafe things, and should ideally be used only through the methods
 //! `encode` and `decode` on types implementing the `Abomonation` trait. Implementing the
-//! `Abomonation` trait is highly discouraged; instead, you can use the [`abomonation_derive` crate](https://crates.io/crates/abomonation_derive).
+//! `Abomonation` trait is highly discouraged; instead, you can use `#[derive(Abomonation)]`,
+//! available with the `derive` feature or from the [`abomonation_derive` crate](https://crates.io/crates/abomonation_derive).
 //!
 //! **Very important**: Abomonation reproduces the memory as laid out by the serializer, which will
 //! reveal architectural variations. Data encoded on a 32bit big-endian machine will not decode
@@ -34,14 +35,69 @@
 //!     assert!(remaining.len() == 0);
 //! }
 //! ```
+//!
+//! # Interior mutability
+//!
+//! `Cell<T>` (for `T: Copy`) and the integer and boolean atomics may be entombed, and updated
+//! in place after decoding, for example as counters shared by several readers of one buffer.
+//! Their values live in the decoded bytes, so updates are visible to later decodes of the same
+//! bytes. Such updates are sound only through references derived from mutably borrowed bytes:
+//! those returned by `decode`, and those reached through `Abomonated` and the `Projected`
+//! handles made from it, which retain a pointer taken from their mutably borrowed bytes.
+//!
+//! The bytes themselves must not be read as a `&[u8]` while such updates may happen, as that
+//! would observe memory mutated through a shared reference. The unsafe constructors of
+//! `Abomonated` make this the caller's obligation for the bytes its `as_bytes` returns, and
+//! for those of the `Projected` handles made from it.
+//!
+//! Encoding copies these values without synchronization, and so must not race with updates
+//! from other threads.
 
 use std::mem;       // yup, used pretty much everywhere.
 use std::io::Write; // for bytes.write_all; push_all is unstable and extend is slow.
 use std::io::Result as IOResult;
 use std::marker::PhantomData;
 use std::num::*;
+use std::borrow::Cow;
+use std::cell::Cell;
+use std::sync::atomic::{AtomicBool, AtomicI8, AtomicI16, AtomicI32, AtomicI64, AtomicIsize};
+use std::sync::atomic::{AtomicU8, AtomicU16, AtomicU32, AtomicU64, AtomicUsize};
+use std::collections::{BinaryHeap, VecDeque};
+use std::sync::OnceLock;
+
+#[cfg(feature = "derive")]
+extern crate abomonation_derive;
+#[cfg(feature = "derive")]
+pub use abomonation_derive::{Abomonation, Entomb, Pod, Viewable};
+
+pub use pod::{Pod, encode_pod, encode_pod_slice, decode_pod, decode_pod_slice};
+pub use frozen::{FrozenHashMap, FrozenBTreeMap};
+pub use flat::{FlatMap, FlatSet};
+pub use time::Timestamp;
+pub use entomb::{Entomb, encode_as, measure_as};
+pub use encoder::{VecEncoder, encode_iter};
+pub use view::{Viewable, decode_view, decode_view_with_limits};
+pub use limits::{DecodeLimits, DecodeError, decode_with_limits, decode_slice_with_limits};
+pub use bounded::encode_bounded;
+pub use error::EncodeError;
 
 pub mod abomonated;
+pub mod bounded;
+pub mod encoder;
+pub mod entomb;
+mod entries;
+pub mod error;
+pub mod flat;
+pub mod frozen;
+pub mod limits;
+mod nesting;
+mod padding;
+pub mod pod;
+mod rc;
+#[cfg(feature = "sanitize")]
+pub mod sanitize;
+pub mod time;
+pub mod view;
 
 /// Encodes a typed reference into a binary buffer.
 ///
@@ -72,8 +128,8 @@ pub mod abomonated;
 ///
 #[inline]
 pub unsafe fn encode<T: Abomonation, W: Write>(typed: &T, write: &mut W) -> IOResult<()> {
-    let slice = std::slice::from_raw_parts(mem::transmute(typed), mem::size_of::<T>());
-    write.write_all(slice)?;
+    let _scope = rc::Scope::enter();
+    typed.entomb_root(write)?;
     typed.entomb(write)?;
     Ok(())
 }
@@ -101,6 +157,9 @@ pub unsafe fn encode<T: Abomonation, W: Write>(typed: &T, write: &mut W) -> IORe
 /// On several platforms unaligned reads are undefined behavior, but on several other platforms they
 /// are only a performance penalty.
 ///
+/// Decoded `Rc` and `Arc` values may be cloned, and the clones are not tied to the lifetime of
+/// `bytes`. The caller must ensure that no such clone is used once `bytes` is dropped or reused.
+///
 /// # Examples
 /// ```
 /// use abomonation::{encode, decode};
@@ -121,11 +180,21 @@ pub unsafe fn encode<T: Abomonation, W: Write>(typed: &T, write: &mut W) -> IORe
 /// ```
 #[inline]
 pub unsafe fn decode<T: Abomonation>(bytes: &mut [u8]) -> Option<(&T, &mut [u8])> {
-    if bytes.len() < mem::size_of::<T>() { None }
+    let _scope = rc::Scope::enter();
+    if bytes.len() < mem::size_of::<T>() { limits::short(bytes, mem::size_of::<T>()); None }
     else {
+        #[cfg(feature = "sanitize")]
+        let lower = bytes.as_ptr() as usize;
         let (split1, split2) = bytes.split_at_mut(mem::size_of::<T>());
-        let result: &mut T = mem::transmute(split1.get_unchecked_mut(0));
-        if let Some(remaining) = result.exhume(split2) {
+        // casts rather than indexes for zero-sized types, whose bytes are empty.
+        let result: &mut T = if mem::size_of::<T>() == 0 { &mut *(split1.as_mut_ptr() as *mut T) }
+                             else { mem::transmute::<&mut u8, &mut T>(split1.get_unchecked_mut(0)) };
+        let remaining = result.exhume(split2);
+        // the outermost box exhumes the contents of boxes nested past `nesting::LIMIT` deep.
+        debug_assert!(nesting::settled(), "deferred exhuming outlived `decode`");
+        if let Some(remaining) = remaining {
+            #[cfg(feature = "sanitize")]
+            sanitize::register(lower, remaining.as_ptr() as usize);
             Some((result, remaining))
         }
         else {
@@ -141,9 +210,87 @@ pub unsafe fn decode<T: Abomonation>(bytes: &mut [u8]) -> Option<(&T, &mut [u8])
 /// The `measure` method is safe. It neither produces nor consults serialized representations.
 #[inline]
 pub fn measure<T: Abomonation>(typed: &T) -> usize {
+    let _scope = rc::Scope::enter();
     mem::size_of::<T>() + typed.extent()
 }
 
+/// Encodes a slice into a binary buffer, preceded by its length.
+///
+/// The length is written as a `usize`, followed by the binary representation of the elements
+/// and then any data they own, as `encode` would for a `Vec<T>`.
+///
+/// # Safety
+///
+/// This method is unsafe for the same reasons as `encode`.
+///
+/// # Examples
+/// ```
+/// use abomonation::{encode_slice, decode_slice};
+///
+/// let strings = ["a", "b", "c"].iter().map(|x| x.to_string()).collect::<Vec<_>>();
+///
+/// let mut bytes = Vec::new();
+/// unsafe { encode_slice(&strings[1..], &mut bytes).unwrap(); }
+///
+/// if let Some((result, remaining)) = unsafe { decode_slice::<String>(&mut bytes) } {
+///     assert!(result == &strings[1..]);
+///     assert!(remaining.len() == 0);
+/// }
+/// ```
+#[inline]
+pub unsafe fn encode_slice<T: Abomonation, W: Write>(slice: &[T], write: &mut W) -> Result<(), EncodeError> {
+    let _scope = rc::Scope::enter();
+    encode(&slice.len(), write)?;
+    write.write_all(typed_to_bytes(slice))?;
+    if T::HAS_INDIRECTION {
+        for element in slice.iter() { element.entomb(write)?; }
+    }
+    Ok(())
+}
+
+/// Decodes a slice written by `encode_slice` from a mutable binary slice.
+///
+/// The return value is either a pair of the typed slice `&[T]` and the remaining `&mut [u8]`
+/// binary data, or `None` if decoding failed due to lack of data.
+///
+/// # Safety
+///
+/// This method is unsafe for the same reasons as `decode`.
+#[inline]
+pub unsafe fn decode_slice<T: Abomonation>(bytes: &mut [u8]) -> Option<(&[T], &mut [u8])> {
+    let _scope = rc::Scope::enter();
+    #[cfg(feature = "sanitize")]
+    let lower = bytes.as_ptr() as usize;
+    let (length, rest) = decode::<usize>(bytes)?;
+    let (slice, rest) = exhume_slice::<T>(*length, rest)?;
+    // the elements back decoded data too, not only the length.
+    #[cfg(feature = "sanitize")]
+    sanitize::register(lower, rest.as_ptr() as usize);
+    Some((&*slice, rest))
+}
+
+/// Encodes a string slice into a binary buffer, preceded by its length.
+///
+/// # Safety
+///
+/// This method is unsafe for the same reasons as `encode`.
+#[inline]
+pub unsafe fn encode_str<W: Write>(string: &str, write: &mut W) -> Result<(), EncodeError> {
+    encode_slice(string.as_bytes(), write)
+}
+
+/// Decodes a string slice written by `encode_str` from a mutable binary slice.
+///
+/// # Safety
+///
+/// This method is unsafe for the same reasons as `decode`; in particular, the bytes are not
+/// checked to be valid utf8.
+#[inline]
+pub unsafe fn decode_str(bytes: &mut [u8]) -> Option<(&str, &mut [u8])> {
+    let (slice, rest) = decode_slice::<u8>(bytes)?;
+    Some((std::str::from_utf8_unchecked(slice), rest))
+}
+
 /// Abomonation provides methods to serialize any heap data the implementor owns.
 ///
 /// The default implementations for Abomonation's methods are all empty. Many types have no owned
@@ -159,6 +306,29 @@ pub fn measure<T: Abomonation>(typed: &T) -> usize {
 /// several things that may be undefined behavior, depending on how undefined behavior is defined.
 pub trait Abomonation {
 
+    /// Indicates whether values of this type may own memory beyond their binary representation.
+    ///
+    /// When `false`, the type promises that `entomb`, `exhume`, and `extent` do nothing, which
+    /// allows containers such as `Vec<T>` to skip visiting each element and to compute their
+    /// `extent` in constant time. The default is the conservative `true`, and types should only
+    /// override it when all of their fields are themselves without indirection.
+    const HAS_INDIRECTION: bool = true;
+
+    /// Write the binary representation of `&self`, as the root of an encoding.
+    ///
+    /// The default writes the bytes of `&self`. Types may instead write fields that `exhume`
+    /// neither reads nor relies on in a canonical form, so that encodings do not depend on
+    /// where owned data were allocated; vectors write a dangling pointer, and a capacity equal
+    /// to their length.
+    ///
+    /// # Safety
+    ///
+    /// This method is unsafe for the same reasons as `encode`, and an override must write
+    /// exactly `mem::size_of_val(self)` bytes that `exhume` accepts in place of `self`.
+    #[inline(always)] unsafe fn entomb_root<W: Write>(&self, write: &mut W) -> IOResult<()> {
+        write.write_all(std::slice::from_raw_parts(self as *const Self as *const u8, mem::size_of_val(self)))
+    }
+
     /// Write any additional information about `&self` beyond its binary representation.
     ///
     /// Most commonly this is owned data on the other end of pointers in `&self`. The return value
@@ -168,6 +338,9 @@ pub trait Abomonation {
     /// Recover any information for `&mut self` not evident from its binary representation.
     ///
     /// Most commonly this populates pointers with valid references into `bytes`.
+    ///
+    /// The contents of deeply nested boxes within `self` may be exhumed only after this returns,
+    /// so implementations must not read through boxes they contain.
     #[inline(always)] unsafe fn exhume<'a,'b>(&'a mut self, bytes: &'b mut [u8]) -> Option<&'b mut [u8]> { Some(bytes) }
 
     /// Reports the number of further bytes required to entomb `self`.
@@ -219,7 +392,9 @@ pub trait Abomonation {
 #[deprecated(since="0.5", note="please use the abomonation_derive crate")]
 macro_rules! unsafe_abomonate {
     ($t:ty) => {
-        impl Abomonation for $t { }
+        impl Abomonation for $t { const HAS_INDIRECTION: bool = false; }
+        $crate::entomb_identity!($t);
+        $crate::viewable_identity!($t);
     };
     ($t:ty : $($field:ident),*) => {
         impl Abomonation for $t {
@@ -237,6 +412,55 @@ macro_rules! unsafe_abomonate {
                 size
             }
         }
+        $crate::entomb_identity!($t);
+        $crate::viewable_identity!($t);
+    };
+}
+
+/// The `assert_abomonable!` macro asserts at compile time that each listed type implements
+/// `Abomonation`.
+///
+/// Types holding references, raw pointers, or shared pointers have no implementation, as the
+/// data they point at would not be entombed and the pointers would dangle after decoding. The
+/// macro can be used next to a manual (or `unsafe_abomonate!`) implementation to check that
+/// each field type of a struct is abomonable.
+///
+/// # Examples
+/// ```
+/// #[macro_use]
+/// extern crate abomonation;
+///
+/// assert_abomonable!(u64, String, Vec<(u64, String)>, Option<Box<[u8; 16]>>);
+///
+/// fn main() { }
+/// ```
+///
+/// The following are rejected:
+/// ```compile_fail
+/// #[macro_use]
+/// extern crate abomonation;
+///
+/// assert_abomonable!(&'static str);
+///
+/// fn main() { }
+/// ```
+/// ```compile_fail
+/// #[macro_use]
+/// extern crate abomonation;
+///
+/// assert_abomonable!(Vec<*const u64>);
+///
+/// fn main() { }
+/// ```
+#[macro_export]
+macro_rules! assert_abomonable {
+    ($($t:ty),+ $(,)*) => {
+        const _: () = {
+            #[allow(dead_code)]
+            fn assert_abomonable<T: ?Sized + $crate::Abomonation>() { }
+            #[allow(dead_code)]
+            fn assert_all() { $( assert_abomonable::<$t>(); )+ }
+        };
     };
 }
 
@@ -244,6 +468,7 @@ macro_rules! unsafe_abomonate {
 macro_rules! tuple_abomonate {
     ( $($name:ident)+) => (
         impl<$($name: Abomonation),*> Abomonation for ($($name,)*) {
+            const HAS_INDIRECTION: bool = false $(|| $name::HAS_INDIRECTION)*;
             #[allow(non_snake_case)]
             #[inline(always)] unsafe fn entomb<WRITE: Write>(&self, write: &mut WRITE) -> IOResult<()> {
                 let ($(ref $name,)*) = *self;
@@ -267,47 +492,78 @@ macro_rules! tuple_abomonate {
     );
 }
 
-impl Abomonation for u8 { }
-impl Abomonation for u16 { }
-impl Abomonation for u32 { }
-impl Abomonation for u64 { }
-impl Abomonation for u128 { }
-impl Abomonation for usize { }
+impl Abomonation for u8 { const HAS_INDIRECTION: bool = false; }
+impl Abomonation for u16 { const HAS_INDIRECTION: bool = false; }
+impl Abomonation for u32 { const HAS_INDIRECTION: bool = false; }
+impl Abomonation for u64 { const HAS_INDIRECTION: bool = false; }
+impl Abomonation for u128 { const HAS_INDIRECTION: bool = false; }
+impl Abomonation for usize { const HAS_INDIRECTION: bool = false; }
 
-impl Abomonation for i8 { }
-impl Abomonation for i16 { }
-impl Abomonation for i32 { }
-impl Abomonation for i64 { }
-impl Abomonation for i128 { }
-impl Abomonation for isize { }
+impl Abomonation for i8 { const HAS_INDIRECTION: bool = false; }
+impl Abomonation for i16 { const HAS_INDIRECTION: bool = false; }
+impl Abomonation for i32 { const HAS_INDIRECTION: bool = false; }
+impl Abomonation for i64 { const HAS_INDIRECTION: bool = false; }
+impl Abomonation for i128 { const HAS_INDIRECTION: bool = false; }
+impl Abomonation for isize { const HAS_INDIRECTION: bool = false; }
 
-impl Abomonation for NonZeroU8 { }
-impl Abomonation for NonZeroU16 { }
-impl Abomonation for NonZeroU32 { }
-impl Abomonation for NonZeroU64 { }
-impl Abomonation for NonZeroU128 { }
-impl Abomonation for NonZeroUsize { }
+impl Abomonation for NonZeroU8 { const HAS_INDIRECTION: bool = false; }
+impl Abomonation for NonZeroU16 { const HAS_INDIRECTION: bool = false; }
+impl Abomonation for NonZeroU32 { const HAS_INDIRECTION: bool = false; }
+impl Abomonation for NonZeroU64 { const HAS_INDIRECTION: bool = false; }
+impl Abomonation for NonZeroU128 { const HAS_INDIRECTION: bool = false; }
+impl Abomonation for NonZeroUsize { const HAS_INDIRECTION: bool = false; }
 
-impl Abomonation for NonZeroI8 { }
-impl Abomonation for NonZeroI16 { }
-impl Abomonation for NonZeroI32 { }
-impl Abomonation for NonZeroI64 { }
-impl Abomonation for NonZeroI128 { }
-impl Abomonation for NonZeroIsize { }
+impl Abomonation for NonZeroI8 { const HAS_INDIRECTION: bool = false; }
+impl Abomonation for NonZeroI16 { const HAS_INDIRECTION: bool = false; }
+impl Abomonation for NonZeroI32 { const HAS_INDIRECTION: bool = false; }
+impl Abomonation for NonZeroI64 { const HAS_INDIRECTION: bool = false; }
+impl Abomonation for NonZeroI128 { const HAS_INDIRECTION: bool = false; }
+impl Abomonation for NonZeroIsize { const HAS_INDIRECTION: bool = false; }
 
-impl Abomonation for f32 { }
-impl Abomonation for f64 { }
+impl Abomonation for f32 { const HAS_INDIRECTION: bool = false; }
+impl Abomonation for f64 { const HAS_INDIRECTION: bool = false; }
 
-impl Abomonation for bool { }
-impl Abomonation for () { }
+impl Abomonation for bool { const HAS_INDIRECTION: bool = false; }
+impl Abomonation for () { const HAS_INDIRECTION: bool = false; }
 
-impl Abomonation for char { }
+impl Abomonation for char { const HAS_INDIRECTION: bool = false; }
 
-impl Abomonation for ::std::time::Duration { }
+// See the crate documentation on interior mutability for the updates sound on decoded data.
+impl<T: Abomonation + Copy> Abomonation for Cell<T> {
+    const HAS_INDIRECTION: bool = T::HAS_INDIRECTION;
+    #[inline(always)] unsafe fn entomb<W: Write>(&self, write: &mut W) -> IOResult<()> {
+        self.get().entomb(write)
+    }
+    #[inline(always)] unsafe fn exhume<'b>(&mut self, bytes: &'b mut[u8]) -> Option<&'b mut [u8]> {
+        self.get_mut().exhume(bytes)
+    }
+    #[inline] fn extent(&self) -> usize {
+        self.get().extent()
+    }
+}
+
+impl Abomonation for AtomicBool { const HAS_INDIRECTION: bool = false; }
+
+impl Abomonation for AtomicU8 { const HAS_INDIRECTION: bool = false; }
+impl Abomonation for AtomicU16 { const HAS_INDIRECTION: bool = false; }
+impl Abomonation for AtomicU32 { const HAS_INDIRECTION: bool = false; }
+impl Abomonation for AtomicU64 { const HAS_INDIRECTION: bool = false; }
+impl Abomonation for AtomicUsize { const HAS_INDIRECTION: bool = false; }
+
+impl Abomonation for AtomicI8 { const HAS_INDIRECTION: bool = false; }
+impl Abomonation for AtomicI16 { const HAS_INDIRECTION: bool = false; }
+impl Abomonation for AtomicI32 { const HAS_INDIRECTION: bool = false; }
+impl Abomonation for AtomicI64 { const HAS_INDIRECTION: bool = false; }
+impl Abomonation for AtomicIsize { const HAS_INDIRECTION: bool = false; }
+
+impl Abomonation for ::std::time::Duration { const HAS_INDIRECTION: bool = false; }
+#[cfg(unix)]
+impl Abomonation for ::std::time::SystemTime { const HAS_INDIRECTION: bool = false; }
 
-impl<T> Abomonation for PhantomData<T> {}
+impl<T> Abomonation for PhantomData<T> { const HAS_INDIRECTION: bool = false; }
 
 impl<T: Abomonation> Abomonation for std::ops::Range<T> {
+    const HAS_INDIRECTION: bool = T::HAS_INDIRECTION;
     #[inline(always)] unsafe fn entomb<W: Write>(&self, write: &mut W) -> IOResult<()> {
         self.start.entomb(write)?;
         self.end.entomb(write)?;
@@ -319,29 +575,154 @@ impl<T: Abomonation> Abomonation for std::ops::Range<T> {
         Some(bytes)
     }
     #[inline] fn extent(&self) -> usize {
-        self.start.extent() << 1
+        self.start.extent() + self.end.extent()
+    }
+}
+
+impl<T: Abomonation> Abomonation for std::ops::RangeInclusive<T> {
+    const HAS_INDIRECTION: bool = T::HAS_INDIRECTION;
+    #[inline(always)] unsafe fn entomb<W: Write>(&self, write: &mut W) -> IOResult<()> {
+        self.start().entomb(write)?;
+        self.end().entomb(write)?;
+        Ok(())
+    }
+    #[inline(always)] unsafe fn exhume<'b>(&mut self, mut bytes: &'b mut[u8]) -> Option<&'b mut [u8]> {
+        // the fields are private; locate them within `self` so that the iteration state is kept.
+        let (start, end) = (field_offset(self, self.start()), field_offset(self, self.end()));
+        let whole = self as *mut Self as *mut u8;
+        let (start, end) = (whole.add(start) as *mut T, whole.add(end) as *mut T);
+        let tmp = bytes; bytes = (*start).exhume(tmp)?;
+        let tmp = bytes; bytes = (*end).exhume(tmp)?;
+        Some(bytes)
+    }
+    #[inline] fn extent(&self) -> usize {
+        self.start().extent() + self.end().extent()
+    }
+}
+
+impl<T: Abomonation> Abomonation for std::ops::RangeFrom<T> {
+    const HAS_INDIRECTION: bool = T::HAS_INDIRECTION;
+    #[inline(always)] unsafe fn entomb<W: Write>(&self, write: &mut W) -> IOResult<()> {
+        self.start.entomb(write)
+    }
+    #[inline(always)] unsafe fn exhume<'b>(&mut self, bytes: &'b mut[u8]) -> Option<&'b mut [u8]> {
+        self.start.exhume(bytes)
+    }
+    #[inline] fn extent(&self) -> usize {
+        self.start.extent()
+    }
+}
+
+impl<T: Abomonation> Abomonation for std::ops::RangeTo<T> {
+    const HAS_INDIRECTION: bool = T::HAS_INDIRECTION;
+    #[inline(always)] unsafe fn entomb<W: Write>(&self, write: &mut W) -> IOResult<()> {
+        self.end.entomb(write)
+    }
+    #[inline(always)] unsafe fn exhume<'b>(&mut self, bytes: &'b mut[u8]) -> Option<&'b mut [u8]> {
+        self.end.exhume(bytes)
+    }
+    #[inline] fn extent(&self) -> usize {
+        self.end.extent()
+    }
+}
+
+impl<T: Abomonation> Abomonation for std::ops::RangeToInclusive<T> {
+    const HAS_INDIRECTION: bool = T::HAS_INDIRECTION;
+    #[inline(always)] unsafe fn entomb<W: Write>(&self, write: &mut W) -> IOResult<()> {
+        self.end.entomb(write)
+    }
+    #[inline(always)] unsafe fn exhume<'b>(&mut self, bytes: &'b mut[u8]) -> Option<&'b mut [u8]> {
+        self.end.exhume(bytes)
+    }
+    #[inline] fn extent(&self) -> usize {
+        self.end.extent()
+    }
+}
+
+impl Abomonation for std::ops::RangeFull { const HAS_INDIRECTION: bool = false; }
+
+impl<T: Abomonation> Abomonation for std::ops::Bound<T> {
+    const HAS_INDIRECTION: bool = T::HAS_INDIRECTION;
+    #[inline(always)] unsafe fn entomb<W: Write>(&self, write: &mut W) -> IOResult<()> {
+        match *self {
+            std::ops::Bound::Included(ref inner) => inner.entomb(write),
+            std::ops::Bound::Excluded(ref inner) => inner.entomb(write),
+            std::ops::Bound::Unbounded => Ok(()),
+        }
+    }
+    #[inline(always)] unsafe fn exhume<'b>(&mut self, bytes: &'b mut[u8]) -> Option<&'b mut [u8]> {
+        match *self {
+            std::ops::Bound::Included(ref mut inner) => inner.exhume(bytes),
+            std::ops::Bound::Excluded(ref mut inner) => inner.exhume(bytes),
+            std::ops::Bound::Unbounded => Some(bytes),
+        }
+    }
+    #[inline] fn extent(&self) -> usize {
+        match *self {
+            std::ops::Bound::Included(ref inner) => inner.extent(),
+            std::ops::Bound::Excluded(ref inner) => inner.extent(),
+            std::ops::Bound::Unbounded => 0,
+        }
     }
 }
 
+// general code for wrappers of a single public field.
+macro_rules! wrapper_abomonate {
+    ($wrapper:path) => (
+        impl<T: Abomonation> Abomonation for $wrapper {
+            const HAS_INDIRECTION: bool = T::HAS_INDIRECTION;
+            #[inline(always)] unsafe fn entomb<W: Write>(&self, write: &mut W) -> IOResult<()> {
+                self.0.entomb(write)
+            }
+            #[inline(always)] unsafe fn exhume<'b>(&mut self, bytes: &'b mut[u8]) -> Option<&'b mut [u8]> {
+                self.0.exhume(bytes)
+            }
+            #[inline] fn extent(&self) -> usize {
+                self.0.extent()
+            }
+        }
+    );
+}
+
+wrapper_abomonate!(std::num::Wrapping<T>);
+wrapper_abomonate!(std::num::Saturating<T>);
+wrapper_abomonate!(std::cmp::Reverse<T>);
+
+impl Abomonation for std::cmp::Ordering { const HAS_INDIRECTION: bool = false; }
+
+/// The offset in bytes of a field within `whole`, given a reference to the field.
+///
+/// This allows the private fields of standard types to be exhumed in place, through pointers
+/// derived from a mutable reference to `whole`.
+#[inline(always)]
+fn field_offset<S, T>(whole: &S, field: &T) -> usize {
+    field as *const T as usize - whole as *const S as usize
+}
+
 impl<T: Abomonation> Abomonation for Option<T> {
+    const HAS_INDIRECTION: bool = T::HAS_INDIRECTION;
     #[inline(always)] unsafe fn entomb<W: Write>(&self, write: &mut W) -> IOResult<()> {
+        if !T::HAS_INDIRECTION { return Ok(()); }
         if let &Some(ref inner) = self {
             inner.entomb(write)?;
         }
         Ok(())
     }
     #[inline(always)] unsafe fn exhume<'a, 'b>(&'a mut self, mut bytes: &'b mut[u8]) -> Option<&'b mut [u8]> {
+        if !T::HAS_INDIRECTION { return Some(bytes); }
         if let &mut Some(ref mut inner) = self {
             let tmp = bytes; bytes = inner.exhume(tmp)?;
         }
         Some(bytes)
     }
     #[inline] fn extent(&self) -> usize {
+        if !T::HAS_INDIRECTION { return 0; }
         self.as_ref().map(|inner| inner.extent()).unwrap_or(0)
     }
 }
 
 impl<T: Abomonation, E: Abomonation> Abomonation for Result<T, E> {
+    const HAS_INDIRECTION: bool = T::HAS_INDIRECTION || E::HAS_INDIRECTION;
     #[inline(always)] unsafe fn entomb<W: Write>(&self, write: &mut W) -> IOResult<()> {
         match self {
             &Ok(ref inner) => inner.entomb(write)?,
@@ -398,19 +779,23 @@ tuple_abomonate!(A B C D E F G H I J K L M N O P Q R S T U V W X Y Z AA AB AC AD
 
 
 impl<T: Abomonation, const N: usize> Abomonation for [T; N] {
+    const HAS_INDIRECTION: bool = T::HAS_INDIRECTION;
     #[inline(always)]
     unsafe fn entomb<W: Write>(&self, write: &mut W) ->  IOResult<()> {
+        if !T::HAS_INDIRECTION { return Ok(()); }
         for element in self { element.entomb(write)?; }
         Ok(())
     }
     #[inline(always)]
     unsafe fn exhume<'a, 'b>(&'a mut self, mut bytes: &'b mut[u8]) -> Option<&'b mut [u8]> {
+        if !T::HAS_INDIRECTION { return Some(bytes); }
         for element in self {
             let tmp = bytes; bytes = element.exhume(tmp)?;
         }
         Some(bytes)
     }
     #[inline(always)] fn extent(&self) -> usize {
+        if !T::HAS_INDIRECTION { return 0; }
         let mut size = 0;
         for element in self {
             size += element.extent();
@@ -428,10 +813,11 @@ impl Abomonation for String {
     }
     #[inline]
     unsafe fn exhume<'a,'b>(&'a mut self, bytes: &'b mut [u8]) -> Option<&'b mut [u8]> {
-        if self.len() > bytes.len() { None }
+        let length = STRING_LENGTH.read(self);
+        if length > bytes.len() { limits::short(bytes, length); None }
         else {
-            let (mine, rest) = bytes.split_at_mut(self.len());
-            std::ptr::write(self, String::from_raw_parts(mem::transmute(mine.as_ptr()), self.len(), self.len()));
+            let (mine, rest) = bytes.split_at_mut(length);
+            std::ptr::write(self, String::from_raw_parts(mine.as_mut_ptr(), length, length));
             Some(rest)
         }
     }
@@ -441,34 +827,35 @@ impl Abomonation for String {
 }
 
 impl<T: Abomonation> Abomonation for Vec<T> {
+    #[inline]
+    unsafe fn entomb_root<W: Write>(&self, write: &mut W) -> IOResult<()> {
+        write.write_all(typed_to_bytes(std::slice::from_ref(&*vec_header::<T>(self.len()))))
+    }
     #[inline]
     unsafe fn entomb<W: Write>(&self, write: &mut W) -> IOResult<()> {
+        padding::pad(mem::align_of::<T>(), write)?;
         write.write_all(typed_to_bytes(&self[..]))?;
-        for element in self.iter() { element.entomb(write)?; }
+        if T::HAS_INDIRECTION {
+            for element in self.iter() { element.entomb(write)?; }
+        }
         Ok(())
     }
     #[inline]
     unsafe fn exhume<'a,'b>(&'a mut self, bytes: &'b mut [u8]) -> Option<&'b mut [u8]> {
 
         // extract memory from bytes to back our vector
-        let binary_len = self.len() * mem::size_of::<T>();
-        if binary_len > bytes.len() { None }
-        else {
-            let (mine, mut rest) = bytes.split_at_mut(binary_len);
-            let slice = std::slice::from_raw_parts_mut(mine.as_mut_ptr() as *mut T, self.len());
-            std::ptr::write(self, Vec::from_raw_parts(slice.as_mut_ptr(), self.len(), self.len()));
-            for element in self.iter_mut() {
-                let temp = rest;             // temp variable explains lifetimes (mysterious!)
-                rest = element.exhume(temp)?;
-            }
-            Some(rest)
-        }
+        let length = VEC_LENGTH.read(self);
+        let (slice, rest) = exhume_slice::<T>(length, bytes)?;
+        std::ptr::write(self, Vec::from_raw_parts(slice as *mut T, length, length));
+        Some(rest)
     }
     #[inline]
     fn extent(&self) -> usize {
         let mut sum = mem::size_of::<T>() * self.len();
-        for element in self.iter() {
-            sum += element.extent();
+        if T::HAS_INDIRECTION {
+            for element in self.iter() {
+                sum += element.extent();
+            }
         }
         sum
     }
@@ -477,23 +864,278 @@ impl<T: Abomonation> Abomonation for Vec<T> {
 impl<T: Abomonation> Abomonation for Box<T> {
     #[inline]
     unsafe fn entomb<W: Write>(&self, bytes: &mut W) -> IOResult<()> {
+        padding::pad(mem::align_of::<T>(), bytes)?;
         bytes.write_all(std::slice::from_raw_parts(mem::transmute(&**self), mem::size_of::<T>()))?;
-        (**self).entomb(bytes)?;
+        // deeply nested boxes defer their contents, rather than overflow the stack.
+        if T::HAS_INDIRECTION { nesting::entomb(&**self, bytes)?; }
         Ok(())
     }
     #[inline]
     unsafe fn exhume<'a,'b>(&'a mut self, bytes: &'b mut [u8]) -> Option<&'b mut [u8]> {
+        let bytes = padding::skip(mem::align_of::<T>(), bytes)?;
         let binary_len = mem::size_of::<T>();
-        if binary_len > bytes.len() { None }
+        if binary_len > bytes.len() { limits::short(bytes, binary_len); None }
         else {
             let (mine, mut rest) = bytes.split_at_mut(binary_len);
-            std::ptr::write(self, mem::transmute(mine.as_mut_ptr() as *mut T));
-            let temp = rest; rest = (**self).exhume(temp)?;
+            std::ptr::write(self, Box::from_raw(mine.as_mut_ptr() as *mut T));
+            if T::HAS_INDIRECTION {
+                let depth = limits::descend()?;
+                let temp = rest;
+                let result = nesting::exhume(&mut **self, temp);
+                limits::ascend(depth);
+                rest = result?;
+            }
             Some(rest)
         }
     }
     #[inline] fn extent(&self) -> usize {
-        mem::size_of::<T>() + (&**self).extent()
+        if !T::HAS_INDIRECTION { return mem::size_of::<T>(); }
+        mem::size_of::<T>() + nesting::extent(&**self)
+    }
+}
+
+impl<T: Abomonation> Abomonation for Box<[T]> {
+    #[inline]
+    unsafe fn entomb<W: Write>(&self, write: &mut W) -> IOResult<()> {
+        padding::pad(mem::align_of::<T>(), write)?;
+        write.write_all(typed_to_bytes(&self[..]))?;
+        if T::HAS_INDIRECTION {
+            for element in self.iter() { element.entomb(write)?; }
+        }
+        Ok(())
+    }
+    #[inline]
+    unsafe fn exhume<'b>(&mut self, bytes: &'b mut [u8]) -> Option<&'b mut [u8]> {
+        // the length is in the fat pointer; avoid referencing the invalid pointee.
+        let length = std::ptr::addr_of!(**self).len();
+        let (slice, rest) = exhume_slice::<T>(length, bytes)?;
+        std::ptr::write(self, Box::from_raw(slice));
+        Some(rest)
+    }
+    #[inline]
+    fn extent(&self) -> usize {
+        let mut sum = mem::size_of::<T>() * self.len();
+        if T::HAS_INDIRECTION {
+            for element in self.iter() {
+                sum += element.extent();
+            }
+        }
+        sum
+    }
+}
+
+impl Abomonation for Box<str> {
+    #[inline]
+    unsafe fn entomb<W: Write>(&self, write: &mut W) -> IOResult<()> {
+        write.write_all(self.as_bytes())?;
+        Ok(())
+    }
+    #[inline]
+    unsafe fn exhume<'b>(&mut self, bytes: &'b mut [u8]) -> Option<&'b mut [u8]> {
+        let length = (std::ptr::addr_of!(**self) as *const [u8]).len();
+        let (slice, rest) = exhume_slice::<u8>(length, bytes)?;
+        std::ptr::write(self, Box::from_raw(slice as *mut str));
+        Some(rest)
+    }
+    #[inline] fn extent(&self) -> usize {
+        self.len()
+    }
+}
+
+// Borrowed and owned strings are entombed alike, and both decode as owned.
+impl Abomonation for Cow<'static, str> {
+    #[inline]
+    unsafe fn entomb<W: Write>(&self, write: &mut W) -> IOResult<()> {
+        write.write_all(self.as_bytes())
+    }
+    #[inline]
+    unsafe fn exhume<'b>(&mut self, bytes: &'b mut [u8]) -> Option<&'b mut [u8]> {
+        let length = match *self {
+            // read the length of the fat pointer without referencing the invalid pointee.
+            Cow::Borrowed(ref string) => (*(string as *const &str as *const *const [u8])).len(),
+            Cow::Owned(ref string) => STRING_LENGTH.read(string),
+        };
+        let (slice, rest) = exhume_slice::<u8>(length, bytes)?;
+        std::ptr::write(self, Cow::Owned(String::from_raw_parts(slice as *mut u8, length, length)));
+        Some(rest)
+    }
+    #[inline] fn extent(&self) -> usize {
+        self.len()
+    }
+}
+
+// Borrowed and owned slices are entombed alike, and both decode as owned.
+impl<T: Abomonation + Clone> Abomonation for Cow<'static, [T]> {
+    #[inline]
+    unsafe fn entomb<W: Write>(&self, write: &mut W) -> IOResult<()> {
+        padding::pad(mem::align_of::<T>(), write)?;
+        write.write_all(typed_to_bytes(&self[..]))?;
+        if T::HAS_INDIRECTION {
+            for element in self.iter() { element.entomb(write)?; }
+        }
+        Ok(())
+    }
+    #[inline]
+    unsafe fn exhume<'b>(&mut self, bytes: &'b mut [u8]) -> Option<&'b mut [u8]> {
+        let length = match *self {
+            Cow::Borrowed(ref slice) => (*(slice as *const &[T] as *const *const [T])).len(),
+            Cow::Owned(ref vector) => VEC_LENGTH.read(vector),
+        };
+        let (slice, rest) = exhume_slice::<T>(length, bytes)?;
+        std::ptr::write(self, Cow::Owned(Vec::from_raw_parts(slice as *mut T, length, length)));
+        Some(rest)
+    }
+    #[inline]
+    fn extent(&self) -> usize {
+        let mut sum = mem::size_of::<T>() * self.len();
+        if T::HAS_INDIRECTION {
+            for element in self.iter() {
+                sum += element.extent();
+            }
+        }
+        sum
+    }
+}
+
+// Entombed as a contiguous slice, and decoded with the head at the start of the buffer.
+impl<T: Abomonation> Abomonation for VecDeque<T> {
+    #[inline]
+    unsafe fn entomb<W: Write>(&self, write: &mut W) -> IOResult<()> {
+        let (front, back) = self.as_slices();
+        padding::pad(mem::align_of::<T>(), write)?;
+        write.write_all(typed_to_bytes(front))?;
+        write.write_all(typed_to_bytes(back))?;
+        if T::HAS_INDIRECTION {
+            for element in self.iter() { element.entomb(write)?; }
+        }
+        Ok(())
+    }
+    #[inline]
+    unsafe fn exhume<'b>(&mut self, bytes: &'b mut [u8]) -> Option<&'b mut [u8]> {
+        let length = VEC_DEQUE_LENGTH.read(self);
+        let (slice, rest) = exhume_slice::<T>(length, bytes)?;
+        // converting from a `Vec` does not reallocate, and places the head at the start.
+        std::ptr::write(self, VecDeque::from(Vec::from_raw_parts(slice as *mut T, length, length)));
+        Some(rest)
+    }
+    #[inline]
+    fn extent(&self) -> usize {
+        let mut sum = mem::size_of::<T>() * self.len();
+        if T::HAS_INDIRECTION {
+            for element in self.iter() {
+                sum += element.extent();
+            }
+        }
+        sum
+    }
+}
+
+// Entombed as its backing vector, which is already in heap order.
+impl<T: Abomonation + Ord> Abomonation for BinaryHeap<T> {
+    #[inline]
+    unsafe fn entomb<W: Write>(&self, write: &mut W) -> IOResult<()> {
+        padding::pad(mem::align_of::<T>(), write)?;
+        write.write_all(typed_to_bytes(self.as_slice()))?;
+        if T::HAS_INDIRECTION {
+            for element in self.iter() { element.entomb(write)?; }
+        }
+        Ok(())
+    }
+    #[inline]
+    unsafe fn exhume<'b>(&mut self, bytes: &'b mut [u8]) -> Option<&'b mut [u8]> {
+        // a heap is only its backing vector, which is exhumed as any other vector.
+        let mut vector = mem::ManuallyDrop::new(std::ptr::read(self).into_vec());
+        let rest = vector.exhume(bytes)?;
+        // the elements are already in heap order; writing the vector in place of the heap avoids
+        // `BinaryHeap::from`, which would heapify them through `T::Ord` before they are exhumed.
+        // a heap of the same size as its only field, the vector, holds it at offset zero.
+        assert!(mem::size_of::<BinaryHeap<T>>() == mem::size_of::<Vec<T>>(), "unsupported BinaryHeap layout");
+        std::ptr::write(self as *mut BinaryHeap<T> as *mut Vec<T>, mem::ManuallyDrop::into_inner(vector));
+        Some(rest)
+    }
+    #[inline]
+    fn extent(&self) -> usize {
+        let mut sum = mem::size_of::<T>() * self.len();
+        if T::HAS_INDIRECTION {
+            for element in self.iter() {
+                sum += element.extent();
+            }
+        }
+        sum
+    }
+}
+
+// `LinkedList` is intentionally not supported: its nodes have a private layout, so they
+// could not be backed by decoded bytes, and decoding would need to allocate new nodes.
+
+/// The word of a kind of collection that holds its length, found once from a probe.
+///
+/// The `len` methods of collections whose fields were read from bytes may assume the length is
+/// that of a valid collection, which a corrupt length need not be, and so may defeat the checks
+/// of `exhume_slice`. Instead, `read` reads the word holding the length directly. The word is
+/// found the first time it is needed, as the one holding three in `probe` of a vector of three
+/// bytes and capacity five. Collections of any element type hold their length in the same word
+/// as the probe, as the fields of their buffers do not depend on the element type.
+struct LengthField<P> {
+    word: OnceLock<usize>,
+    probe: fn(Vec<u8>) -> P,
+}
+
+impl<P> LengthField<P> {
+    const fn new(probe: fn(Vec<u8>) -> P) -> Self {
+        LengthField { word: OnceLock::new(), probe }
+    }
+    /// Reads the length of `collection`, which must be laid out as the probe is.
+    #[inline]
+    unsafe fn read<C>(&self, collection: &C) -> usize {
+        assert!(mem::size_of::<P>() == mem::size_of::<C>(), "collection and probe layouts differ");
+        let word = *self.word.get_or_init(|| {
+            let mut vector = Vec::with_capacity(5);
+            vector.resize(3, 0u8);
+            let probe = (self.probe)(vector);
+            let words = std::slice::from_raw_parts(&probe as *const P as *const usize, mem::size_of::<P>() / mem::size_of::<usize>());
+            words.iter().position(|&word| word == 3).expect("collection length not found")
+        });
+        (collection as *const C as *const usize).add(word).read()
+    }
+}
+
+static VEC_LENGTH: LengthField<Vec<u8>> = LengthField::new(|probe| probe);
+static STRING_LENGTH: LengthField<String> = LengthField::new(|probe| String::from_utf8(probe).unwrap());
+static VEC_DEQUE_LENGTH: LengthField<VecDeque<u8>> = LengthField::new(VecDeque::from);
+
+/// A vector of `length` elements as it is encoded, with a dangling pointer and a capacity equal
+/// to its length. Only the length is read when decoding.
+#[inline]
+unsafe fn vec_header<T>(length: usize) -> mem::ManuallyDrop<Vec<T>> {
+    mem::ManuallyDrop::new(Vec::from_raw_parts(std::ptr::NonNull::dangling().as_ptr(), length, length))
+}
+
+/// Uses a prefix of `bytes` to back `length` elements, and exhumes each of them.
+#[inline]
+unsafe fn exhume_slice<T: Abomonation>(length: usize, bytes: &mut [u8]) -> Option<(*mut [T], &mut [u8])> {
+    if !limits::elements(length) { return None; }
+    let bytes = padding::skip(mem::align_of::<T>(), bytes)?;
+    let binary_len = match length.checked_mul(mem::size_of::<T>()) {
+        Some(binary_len) => binary_len,
+        None => { limits::overflow(); return None; },
+    };
+    if binary_len > bytes.len() { limits::short(bytes, binary_len); None }
+    else {
+        let (mine, mut rest) = bytes.split_at_mut(binary_len);
+        let slice = std::slice::from_raw_parts_mut(mine.as_mut_ptr() as *mut T, length);
+        if T::HAS_INDIRECTION {
+            let depth = limits::descend()?;
+            for element in slice.iter_mut() {
+                let temp = rest;             // temp variable explains lifetimes (mysterious!)
+                match element.exhume(temp) {
+                    Some(temp) => rest = temp,
+                    None => { limits::ascend(depth); return None; },
+                }
+            }
+            limits::ascend(depth);
+        }
+        Some((slice as *mut [T], rest))
     }
 }
 
@@ -506,11 +1148,110 @@ mod network {
     use Abomonation;
     use std::net::{SocketAddr, SocketAddrV4, SocketAddrV6, IpAddr, Ipv4Addr, Ipv6Addr};
 
-    impl Abomonation for IpAddr { }
-    impl Abomonation for Ipv4Addr { }
-    impl Abomonation for Ipv6Addr { }
+    impl Abomonation for IpAddr { const HAS_INDIRECTION: bool = false; }
+    impl Abomonation for Ipv4Addr { const HAS_INDIRECTION: bool = false; }
+    impl Abomonation for Ipv6Addr { const HAS_INDIRECTION: bool = false; }
 
-    impl Abomonation for SocketAddr { }
-    impl Abomonation for SocketAddrV4 { }
-    impl Abomonation for SocketAddrV6 { }
+    impl Abomonation for SocketAddr { const HAS_INDIRECTION: bool = false; }
+    impl Abomonation for SocketAddrV4 { const HAS_INDIRECTION: bool = false; }
+    impl Abomonation for SocketAddrV6 { const HAS_INDIRECTION: bool = false; }
+}
+
+mod ffi {
+    use Abomonation;
+    use super::exhume_slice;
+    use std::ffi::{CStr, CString};
+    use std::io::Write;
+    use std::io::Result as IOResult;
+    use std::mem;
+
+    // The entombed bytes include the trailing NUL. Exhuming checks that this is the only NUL,
+    // and fails otherwise, so that decoded values uphold the invariant of `CString`.
+    impl Abomonation for CString {
+        #[inline]
+        unsafe fn entomb<W: Write>(&self, write: &mut W) -> IOResult<()> {
+            write.write_all(self.as_bytes_with_nul())
+        }
+        #[inline]
+        unsafe fn exhume<'b>(&mut self, bytes: &'b mut [u8]) -> Option<&'b mut [u8]> {
+            // `CString` holds only a `Box<[u8]>`; read its length without referencing the invalid pointee.
+            let length = mem::transmute_copy::<CString, *const [u8]>(self).len();
+            let (slice, rest) = exhume_slice::<u8>(length, bytes)?;
+            CStr::from_bytes_with_nul(&*slice).ok()?;
+            // the vector's length equals its capacity, so boxing it does not reallocate.
+            std::ptr::write(self, CString::from_vec_with_nul_unchecked(Vec::from_raw_parts(slice as *mut u8, length, length)));
+            Some(rest)
+        }
+        #[inline] fn extent(&self) -> usize {
+            self.as_bytes_with_nul().len()
+        }
+    }
+
+    impl Abomonation for Box<CStr> {
+        #[inline]
+        unsafe fn entomb<W: Write>(&self, write: &mut W) -> IOResult<()> {
+            write.write_all(self.to_bytes_with_nul())
+        }
+        #[inline]
+        unsafe fn exhume<'b>(&mut self, bytes: &'b mut [u8]) -> Option<&'b mut [u8]> {
+            let length = (std::ptr::addr_of!(**self) as *const [u8]).len();
+            let (slice, rest) = exhume_slice::<u8>(length, bytes)?;
+            CStr::from_bytes_with_nul(&*slice).ok()?;
+            std::ptr::write(self, Box::from_raw(slice as *mut CStr));
+            Some(rest)
+        }
+        #[inline] fn extent(&self) -> usize {
+            self.to_bytes_with_nul().len()
+        }
+    }
+
+    // On unix platforms `OsString` and `PathBuf` are byte vectors, and are entombed like `String`.
+    #[cfg(unix)]
+    mod unix {
+        use Abomonation;
+        use {exhume_slice, LengthField};
+        use std::ffi::OsString;
+        use std::io::Write;
+        use std::io::Result as IOResult;
+        use std::os::unix::ffi::{OsStrExt, OsStringExt};
+        use std::path::PathBuf;
+
+        static OS_STRING_LENGTH: LengthField<OsString> = LengthField::new(OsString::from_vec);
+        static PATH_BUF_LENGTH: LengthField<PathBuf> = LengthField::new(|probe| PathBuf::from(OsString::from_vec(probe)));
+
+        impl Abomonation for OsString {
+            #[inline]
+            unsafe fn entomb<W: Write>(&self, write: &mut W) -> IOResult<()> {
+                write.write_all(self.as_bytes())
+            }
+            #[inline]
+            unsafe fn exhume<'b>(&mut self, bytes: &'b mut [u8]) -> Option<&'b mut [u8]> {
+                let length = OS_STRING_LENGTH.read(self);
+                let (slice, rest) = exhume_slice::<u8>(length, bytes)?;
+                std::ptr::write(self, OsString::from_vec(Vec::from_raw_parts(slice as *mut u8, length, length)));
+                Some(rest)
+            }
+            #[inline] fn extent(&self) -> usize {
+                self.len()
+            }
+        }
+
+        impl Abomonation for PathBuf {
+            #[inline]
+            unsafe fn entomb<W: Write>(&self, write: &mut W) -> IOResult<()> {
+                write.write_all(self.as_os_str().as_bytes())
+            }
+            #[inline]
+            unsafe fn exhume<'b>(&mut self, bytes: &'b mut [u8]) -> Option<&'b mut [u8]> {
+                let length = PATH_BUF_LENGTH.read(self);
+                let (slice, rest) = exhume_slice::<u8>(length, bytes)?;
+                let string = OsString::from_vec(Vec::from_raw_parts(slice as *mut u8, length, length));
+                std::ptr::write(self, PathBuf::from(string));
+                Some(rest)
+            }
+            #[inline] fn extent(&self) -> usize {
+                self.as_os_str().len()
+            }
+        }
+    }
 }
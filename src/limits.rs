@@ -0,0 +1,232 @@
+//! Limits on the resources used to decode untrusted bytes.
+//!
+//! Lengths read from the bytes determine how much work decoding does: a `Vec<Vec<()>>` whose
+//! inner vector claims `1 << 62` elements occupies a few bytes, but exhuming it would visit
+//! each of those elements. `decode_with_limits`, `decode_view_with_limits`, and
+//! `Abomonated::new_with_limits` decode as `decode`, `decode_view`, and `Abomonated::new` do,
+//! but stop with a `DecodeError` once the bytes exceed any of the limits of a `DecodeLimits`.
+//!
+//! Limits do not make decoding safe: the bytes must still be a valid encoding of a `T`. They
+//! bound the work and memory that a corrupt or hostile encoding can cause.
+//!
+//! # Examples
+//! ```
+//! use abomonation::{encode, decode_with_limits, DecodeLimits, DecodeError};
+//!
+//! let vector = vec![vec![(); 1 << 40]];
+//!
+//! let mut bytes = Vec::new();
+//! unsafe { encode(&vector, &mut bytes).unwrap(); }
+//!
+//! let limits = DecodeLimits { max_elements: 1 << 20, ..DecodeLimits::default() };
+//! let result = unsafe { decode_with_limits::<Vec<Vec<()>>>(&mut bytes, &limits) };
+//! assert_eq!(result.err(), Some(DecodeError::ElementsExceeded));
+//! ```
+
+use std::cell::Cell;
+use std::error::Error;
+use std::fmt;
+
+use {Abomonation, decode, decode_slice};
+
+/// Limits on the bytes decoded, and on the structure they describe.
+///
+/// The default imposes no limits.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct DecodeLimits {
+    /// The most bytes that decoding may consume.
+    pub max_bytes: usize,
+    /// The most boxes and collections of values with owned data that may nest in one another.
+    pub max_depth: usize,
+    /// The most elements of any one decoded collection.
+    pub max_elements: usize,
+}
+
+impl Default for DecodeLimits {
+    fn default() -> Self {
+        DecodeLimits { max_bytes: usize::MAX, max_depth: usize::MAX, max_elements: usize::MAX }
+    }
+}
+
+/// The reason decoding with limits failed.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum DecodeError {
+    /// The bytes do not decode as the type, for example as they are truncated.
+    Invalid,
+    /// A length read from the bytes overflowed the computation of a size.
+    Overflow,
+    /// Decoding would consume more than `max_bytes` bytes.
+    BytesExceeded,
+    /// Boxes or collections nest more than `max_depth` deep.
+    DepthExceeded,
+    /// A collection has more than `max_elements` elements.
+    ElementsExceeded,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            DecodeError::Invalid => "bytes do not decode as the type",
+            DecodeError::Overflow => "size computation overflowed",
+            DecodeError::BytesExceeded => "decoding limit on bytes exceeded",
+            DecodeError::DepthExceeded => "decoding limit on nesting depth exceeded",
+            DecodeError::ElementsExceeded => "decoding limit on collection elements exceeded",
+        })
+    }
+}
+
+impl Error for DecodeError { }
+
+/// The limits in effect on this thread, with the state of their enforcement.
+#[derive(Copy, Clone)]
+struct Active {
+    limits: DecodeLimits,
+    depth: usize,
+    failure: Option<DecodeError>,
+    /// The address up to which the first read past the end of the bytes needed them.
+    short: Option<usize>,
+}
+
+thread_local!(static ACTIVE: Cell<Option<Active>> = const { Cell::new(None) });
+
+/// Records the first failure of the active limits, if any.
+#[inline]
+fn fail(error: DecodeError) {
+    ACTIVE.with(|active| {
+        if let Some(mut state) = active.get() {
+            if state.failure.is_none() { state.failure = Some(error); active.set(Some(state)); }
+        }
+    });
+}
+
+/// Records that a read of `needed` bytes found only `bytes`.
+///
+/// This lets `within` tell a read cut short by `max_bytes` from one that no bytes would satisfy.
+#[cold]
+pub(crate) fn short(bytes: &[u8], needed: usize) {
+    ACTIVE.with(|active| {
+        if let Some(mut state) = active.get() {
+            if state.short.is_none() {
+                state.short = Some((bytes.as_ptr() as usize).saturating_add(needed));
+                active.set(Some(state));
+            }
+        }
+    });
+}
+
+/// Indicates whether a collection of `length` elements is within the active limits.
+#[inline]
+pub(crate) fn elements(length: usize) -> bool {
+    ACTIVE.with(|active| match active.get() {
+        Some(state) if length > state.limits.max_elements => { fail(DecodeError::ElementsExceeded); false },
+        _ => true,
+    })
+}
+
+/// Records that the size of a collection overflowed.
+#[inline]
+pub(crate) fn overflow() {
+    fail(DecodeError::Overflow);
+}
+
+/// Enters the contents of a box or collection, returning the previous depth, or `None` if this
+/// exceeds the active limits.
+#[inline]
+pub(crate) fn descend() -> Option<usize> {
+    ACTIVE.with(|active| match active.get() {
+        None => Some(0),
+        Some(mut state) => {
+            let depth = state.depth;
+            if depth == state.limits.max_depth { fail(DecodeError::DepthExceeded); None }
+            else { state.depth = depth + 1; active.set(Some(state)); Some(depth) }
+        },
+    })
+}
+
+/// The current depth, as of entering some contents; restored by `ascend`.
+#[inline]
+pub(crate) fn depth() -> usize {
+    ACTIVE.with(|active| active.get().map(|state| state.depth).unwrap_or(0))
+}
+
+/// Returns to the depth `depth`.
+#[inline]
+pub(crate) fn ascend(depth: usize) {
+    ACTIVE.with(|active| {
+        if let Some(mut state) = active.get() { state.depth = depth; active.set(Some(state)); }
+    });
+}
+
+/// Makes `limits` active on this thread, until dropped.
+struct Scope {
+    previous: Option<Active>,
+}
+
+impl Scope {
+    fn enter(limits: &DecodeLimits) -> Self {
+        let active = Active { limits: *limits, depth: 0, failure: None, short: None };
+        Scope { previous: ACTIVE.with(|cell| cell.replace(Some(active))) }
+    }
+    fn failure(&self) -> Option<DecodeError> {
+        ACTIVE.with(|active| active.get().and_then(|state| state.failure))
+    }
+    fn short(&self) -> Option<usize> {
+        ACTIVE.with(|active| active.get().and_then(|state| state.short))
+    }
+}
+
+impl Drop for Scope {
+    fn drop(&mut self) {
+        ACTIVE.with(|active| active.set(self.previous));
+    }
+}
+
+/// Applies `decode` to at most `limits.max_bytes` of `bytes`, with the other limits active.
+///
+/// `decode` returns its result and the number of bytes it left, and this returns the result
+/// and the number of bytes it consumed.
+///
+/// Decoding fails with `DecodeError::BytesExceeded` only if it failed reading past the end of
+/// the truncated bytes, where the bytes beyond `max_bytes` would have sufficed.
+unsafe fn within<R, F>(bytes: &mut [u8], limits: &DecodeLimits, decode: F) -> Result<(R, usize), DecodeError>
+where
+    F: FnOnce(&mut [u8]) -> Option<(R, usize)>,
+{
+    let scope = Scope::enter(limits);
+    let length = bytes.len().min(limits.max_bytes);
+    let end = bytes.as_ptr() as usize + bytes.len();
+    match decode(&mut bytes[.. length]) {
+        Some((result, remaining)) => Ok((result, length - remaining)),
+        None => Err(scope.failure().unwrap_or(match scope.short() {
+            Some(needed) if length < bytes.len() && needed <= end => DecodeError::BytesExceeded,
+            _ => DecodeError::Invalid,
+        })),
+    }
+}
+
+/// Decodes a mutable binary slice into an immutable typed reference, within `limits`.
+///
+/// This decodes as `decode` does, but fails with a `DecodeError` rather than `None`, and fails
+/// once the bytes exceed any of `limits`.
+///
+/// # Safety
+///
+/// This method is unsafe for the same reasons as `decode`.
+pub unsafe fn decode_with_limits<'a, T: Abomonation>(bytes: &'a mut [u8], limits: &DecodeLimits) -> Result<(&'a T, &'a mut [u8]), DecodeError> {
+    let (result, consumed) = within(bytes, limits, |bytes| {
+        decode::<T>(bytes).map(|(result, rest)| (result as *const T, rest.len()))
+    })?;
+    Ok((&*result, &mut bytes[consumed ..]))
+}
+
+/// Decodes a slice written by `encode_slice` from a mutable binary slice, within `limits`.
+///
+/// # Safety
+///
+/// This method is unsafe for the same reasons as `decode`.
+pub unsafe fn decode_slice_with_limits<'a, T: Abomonation>(bytes: &'a mut [u8], limits: &DecodeLimits) -> Result<(&'a [T], &'a mut [u8]), DecodeError> {
+    let (result, consumed) = within(bytes, limits, |bytes| {
+        decode_slice::<T>(bytes).map(|(result, rest)| (result as *const [T], rest.len()))
+    })?;
+    Ok((&*result, &mut bytes[consumed ..]))
+}
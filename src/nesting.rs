@@ -0,0 +1,162 @@
+//! Bounded recursion through nested boxes.
+//!
+//! Entombing, exhuming, or measuring a `Box<T>` recurses into `T`, so a structure such as a
+//! linked list of `Option<Box<Node>>` would recurse once per node and overflow the stack.
+//! Instead, once boxes are nested `LIMIT` deep, the contents of further boxes are deferred to
+//! a queue, which the outermost box works through after its own contents. Entombing and
+//! exhuming defer in the same way, so the deferred contents are read in the order written.
+//!
+//! Structures nested less than `LIMIT` boxes deep are written exactly as by plain recursion.
+//!
+//! Deferred contents are exhumed only once the outermost box's own `exhume` has returned, and
+//! until then they hold the pointers they were entombed with. An `exhume` implementation must
+//! therefore not read through boxes in the value it exhumes, for example to validate or order
+//! its elements; it may only read the bytes it consumes itself.
+//!
+//! Entombing, exhuming, and measuring each have a depth and a queue of their own, so that for
+//! example `measure` called from within an `entomb` implementation neither defers its work to
+//! the queue of the entombing box nor mistakes itself for being nested.
+//!
+//! The depth and the queue are restored by a guard, so that a panic part way through (in an
+//! `entomb` or `extent` implementation, or a writer) leaves neither a raised depth nor deferred
+//! pointers to values that may no longer exist.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::io::Write;
+use std::io::Result as IOResult;
+use std::thread::LocalKey;
+
+use Abomonation;
+use limits;
+
+/// The number of nested boxes handled by recursion.
+pub(crate) const LIMIT: usize = 512;
+
+/// Entombs the contents of a deferred box.
+type EntombWork = (*const u8, unsafe fn(*const u8, &mut dyn Write) -> IOResult<()>);
+/// Exhumes the contents of a deferred box, also recording their depth for `DecodeLimits`.
+type ExhumeWork = (*mut u8, for<'b> unsafe fn(*mut u8, &'b mut [u8]) -> Option<&'b mut [u8]>, usize);
+/// Measures the contents of a deferred box.
+type ExtentWork = (*const u8, fn(*const u8) -> usize);
+
+struct State<D> {
+    /// The number of boxes whose contents are currently being visited.
+    depth: usize,
+    /// Contents of boxes nested too deeply, in the order they were reached.
+    deferred: VecDeque<D>,
+}
+
+impl<D> Default for State<D> {
+    fn default() -> Self {
+        State { depth: 0, deferred: VecDeque::new() }
+    }
+}
+
+thread_local!(static ENTOMB: RefCell<State<EntombWork>> = RefCell::new(State::default()));
+thread_local!(static EXHUME: RefCell<State<ExhumeWork>> = RefCell::new(State::default()));
+thread_local!(static EXTENT: RefCell<State<ExtentWork>> = RefCell::new(State::default()));
+
+/// The contents of a box being visited, until dropped.
+struct Guard<D: 'static> {
+    state: &'static LocalKey<RefCell<State<D>>>,
+    /// The depth before entering the contents.
+    depth: usize,
+}
+
+impl<D> Guard<D> {
+    /// Enters the contents of a box, or defers `work` and returns `None` if boxes are already
+    /// nested `LIMIT` deep.
+    #[inline]
+    fn enter<F: FnOnce() -> D>(state: &'static LocalKey<RefCell<State<D>>>, work: F) -> Option<Guard<D>> {
+        state.with(|inner| {
+            let mut inner = inner.borrow_mut();
+            let depth = inner.depth;
+            if depth == LIMIT { inner.deferred.push_back(work()); None }
+            else { inner.depth = depth + 1; Some(Guard { state, depth }) }
+        })
+    }
+    /// Takes the next deferred work if this is the outermost box, which is responsible for it.
+    #[inline]
+    fn next(&self) -> Option<D> {
+        if self.depth > 0 { return None; }
+        self.state.with(|inner| inner.borrow_mut().deferred.pop_front())
+    }
+}
+
+impl<D> Drop for Guard<D> {
+    /// Restores the previous depth and, leaving the outermost box, discards any deferred work
+    /// left by a failure or a panic.
+    #[inline]
+    fn drop(&mut self) {
+        // the state may already be destroyed if a thread panics during its teardown.
+        let _ = self.state.try_with(|inner| {
+            let mut inner = inner.borrow_mut();
+            inner.depth = self.depth;
+            if self.depth == 0 { inner.deferred.clear(); }
+        });
+    }
+}
+
+/// Indicates that no exhuming is deferred outside of a box, as should hold once `decode` returns.
+pub(crate) fn settled() -> bool {
+    EXHUME.with(|inner| { let inner = inner.borrow(); inner.depth > 0 || inner.deferred.is_empty() })
+}
+
+/// Entombs the contents of a box.
+#[inline]
+pub(crate) unsafe fn entomb<T: Abomonation, W: Write>(value: &T, write: &mut W) -> IOResult<()> {
+    let guard = match Guard::enter(&ENTOMB, || (value as *const T as *const u8, entomb_erased::<T> as _)) {
+        Some(guard) => guard,
+        None => return Ok(()),
+    };
+    value.entomb(write)?;
+    while let Some((value, entomb)) = guard.next() {
+        entomb(value, write)?;
+    }
+    Ok(())
+}
+
+/// Exhumes the contents of a box.
+#[inline]
+pub(crate) unsafe fn exhume<'b, T: Abomonation>(value: &mut T, bytes: &'b mut [u8]) -> Option<&'b mut [u8]> {
+    let guard = match Guard::enter(&EXHUME, || (value as *mut T as *mut u8, exhume_erased::<T> as _, limits::depth())) {
+        Some(guard) => guard,
+        None => return Some(bytes),
+    };
+    let mut bytes = value.exhume(bytes)?;
+    while let Some((value, exhume, depth)) = guard.next() {
+        let outer = limits::depth();
+        limits::ascend(depth);
+        let result = exhume(value, bytes);
+        limits::ascend(outer);
+        bytes = result?;
+    }
+    Some(bytes)
+}
+
+/// Measures the contents of a box.
+#[inline]
+pub(crate) fn extent<T: Abomonation>(value: &T) -> usize {
+    let guard = match Guard::enter(&EXTENT, || (value as *const T as *const u8, extent_erased::<T> as _)) {
+        Some(guard) => guard,
+        None => return 0,
+    };
+    let mut sum = value.extent();
+    while let Some((value, extent)) = guard.next() {
+        sum += extent(value);
+    }
+    sum
+}
+
+unsafe fn entomb_erased<T: Abomonation>(value: *const u8, mut write: &mut dyn Write) -> IOResult<()> {
+    (*(value as *const T)).entomb(&mut write)
+}
+
+unsafe fn exhume_erased<T: Abomonation>(value: *mut u8, bytes: &mut [u8]) -> Option<&mut [u8]> {
+    (*(value as *mut T)).exhume(bytes)
+}
+
+fn extent_erased<T: Abomonation>(value: *const u8) -> usize {
+    unsafe { (*(value as *const T)).extent() }
+}
@@ -0,0 +1,107 @@
+//! Padding that aligns the data values own, for the bytes of `Abomonated::from_value`.
+//!
+//! `encode` writes the data a value owns without padding, so that the contents of a
+//! `Vec<u128>` following those of a `String` of odd length are misaligned. While a `Scope`
+//! is active, each region of owned data whose alignment exceeds one is instead preceded by
+//! zero bytes up to a multiple of its alignment, counted from the start of the encoding, and
+//! exhuming skips those bytes. Decoding bytes whose start is aligned to the greatest alignment
+//! of any region then finds every region at an aligned address.
+//!
+//! Outside of a scope nothing is padded, and encodings are exactly those of `encode`. Shared
+//! pointers refuse to encode within a scope, as clones of decoded `Rc` and `Arc` values could
+//! outlive the bytes that `from_value` owns.
+
+use std::cell::Cell;
+use std::io::{Read, Write};
+use std::io::Result as IOResult;
+
+#[derive(Copy, Clone)]
+enum Mode {
+    /// The number of bytes written so far, and the greatest alignment padded to.
+    Encoding { position: usize, align: usize },
+    Decoding,
+}
+
+thread_local!(static MODE: Cell<Option<Mode>> = const { Cell::new(None) });
+
+/// Padding for the duration of an `encode` or `decode` call.
+///
+/// Dropping the scope restores the mode of any enclosing scope.
+pub(crate) struct Scope {
+    previous: Option<Mode>,
+}
+
+impl Scope {
+    /// Pads regions written through a `Counted` writer.
+    pub(crate) fn encoding() -> Self {
+        Scope { previous: MODE.with(|mode| mode.replace(Some(Mode::Encoding { position: 0, align: 1 }))) }
+    }
+    /// Skips the padding preceding exhumed regions.
+    pub(crate) fn decoding() -> Self {
+        Scope { previous: MODE.with(|mode| mode.replace(Some(Mode::Decoding))) }
+    }
+    /// The number of bytes written within the scope, and the greatest alignment padded to.
+    pub(crate) fn written(&self) -> (usize, usize) {
+        match MODE.with(|mode| mode.get()) {
+            Some(Mode::Encoding { position, align }) => (position, align),
+            _ => (0, 1),
+        }
+    }
+}
+
+impl Drop for Scope {
+    fn drop(&mut self) {
+        MODE.with(|mode| mode.set(self.previous));
+    }
+}
+
+/// Indicates that a scope is active, in which case shared pointers are not supported.
+#[inline]
+pub(crate) fn active() -> bool {
+    MODE.with(|mode| mode.get().is_some())
+}
+
+/// A writer that counts the bytes written through it, which padding is measured from.
+pub(crate) struct Counted<W>(pub W);
+
+impl<W: Write> Write for Counted<W> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> IOResult<usize> {
+        let written = self.0.write(buf)?;
+        MODE.with(|mode| {
+            if let Some(Mode::Encoding { position, align }) = mode.get() {
+                mode.set(Some(Mode::Encoding { position: position + written, align }));
+            }
+        });
+        Ok(written)
+    }
+    #[inline]
+    fn flush(&mut self) -> IOResult<()> {
+        self.0.flush()
+    }
+}
+
+/// Writes the padding preceding a region of alignment `align`, within an encoding scope.
+#[inline]
+pub(crate) fn pad<W: Write>(align: usize, write: &mut W) -> IOResult<()> {
+    if align == 1 { return Ok(()); }
+    let padding = MODE.with(|mode| match mode.get() {
+        Some(Mode::Encoding { position, align: greatest }) => {
+            mode.set(Some(Mode::Encoding { position, align: greatest.max(align) }));
+            position.wrapping_neg() & (align - 1)
+        },
+        _ => 0,
+    });
+    if padding > 0 {
+        std::io::copy(&mut std::io::repeat(0).take(padding as u64), write)?;
+    }
+    Ok(())
+}
+
+/// Skips the padding preceding a region of alignment `align`, within a decoding scope.
+#[inline]
+pub(crate) fn skip(align: usize, bytes: &mut [u8]) -> Option<&mut [u8]> {
+    if align == 1 || !MODE.with(|mode| matches!(mode.get(), Some(Mode::Decoding))) { return Some(bytes); }
+    let padding = (bytes.as_ptr() as usize).wrapping_neg() & (align - 1);
+    bytes.get_mut(padding ..)
+}
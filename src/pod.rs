@@ -0,0 +1,142 @@
+//! Plain old data, which can be encoded and decoded safely.
+//!
+//! Types implementing `Pod` are `Copy`, contain no padding bytes, and are valid for any bit
+//! pattern. Their binary representation can be written without exposing uninitialized memory,
+//! and any suitably sized and aligned bytes can be read back as a valid value. This allows
+//! `encode_pod` and `decode_pod` to be safe, unlike `encode` and `decode`.
+//!
+//! The encoded form of a `Pod` type is the same as produced by `encode`, for those types that
+//! also implement `Abomonation`.
+//!
+//! # Examples
+//! ```
+//! use abomonation::{encode_pod_slice, decode_pod_slice};
+//! use abomonation::abomonated::AlignedBytes;
+//!
+//! let values = (0 .. 256u64).collect::<Vec<_>>();
+//!
+//! let mut bytes = Vec::new();
+//! encode_pod_slice(&values[..], &mut bytes).unwrap();
+//!
+//! // `decode_pod_slice` requires aligned bytes, which `Vec<u8>` does not promise.
+//! let mut aligned = AlignedBytes::new(bytes.len(), std::mem::align_of::<u64>());
+//! aligned.copy_from_slice(&bytes);
+//!
+//! assert_eq!(decode_pod_slice::<u64>(&aligned), Some(&values[..]));
+//! ```
+
+use std::io::Write;
+use std::mem;
+
+use error::EncodeError;
+
+/// Types without padding bytes, valid for every bit pattern, and owning no memory.
+///
+/// This trait may be derived (with the `derive` feature) for `#[repr(C)]` and
+/// `#[repr(transparent)]` structs whose fields are all `Pod`; the derive checks at
+/// compile time that the struct contains no padding.
+///
+/// # Safety
+///
+/// Implementors must be inhabited, contain no padding bytes (including trailing padding),
+/// no references or pointers, and every bit pattern of `mem::size_of::<Self>()` bytes must
+/// be a valid value. For example, `bool`, `char`, and `NonZeroU32` are not `Pod`, nor are
+/// tuples, whose layout is unspecified.
+pub unsafe trait Pod: Copy + 'static { }
+
+unsafe impl Pod for u8 { }
+unsafe impl Pod for u16 { }
+unsafe impl Pod for u32 { }
+unsafe impl Pod for u64 { }
+unsafe impl Pod for u128 { }
+unsafe impl Pod for usize { }
+
+unsafe impl Pod for i8 { }
+unsafe impl Pod for i16 { }
+unsafe impl Pod for i32 { }
+unsafe impl Pod for i64 { }
+unsafe impl Pod for i128 { }
+unsafe impl Pod for isize { }
+
+unsafe impl Pod for f32 { }
+unsafe impl Pod for f64 { }
+
+unsafe impl<T: Pod, const N: usize> Pod for [T; N] { }
+
+/// The binary representation of a slice of plain data.
+#[inline]
+pub fn pod_bytes<T: Pod>(slice: &[T]) -> &[u8] {
+    // Safe because `T` has no padding bytes to expose.
+    unsafe { std::slice::from_raw_parts(slice.as_ptr() as *const u8, mem::size_of_val(slice)) }
+}
+
+/// Encodes plain data into a binary buffer.
+///
+/// The written bytes are exactly `mem::size_of::<T>()` bytes of `typed`.
+#[inline]
+pub fn encode_pod<T: Pod, W: Write>(typed: &T, write: &mut W) -> Result<(), EncodeError> {
+    write.write_all(pod_bytes(std::slice::from_ref(typed)))?;
+    Ok(())
+}
+
+/// Encodes a slice of plain data into a binary buffer, without a length.
+#[inline]
+pub fn encode_pod_slice<T: Pod, W: Write>(slice: &[T], write: &mut W) -> Result<(), EncodeError> {
+    write.write_all(pod_bytes(slice))?;
+    Ok(())
+}
+
+/// Decodes a reference to plain data from the front of a binary slice.
+///
+/// The return value is the typed reference and the remaining bytes, or `None` if `bytes`
+/// is too short or its start is not aligned to `mem::align_of::<T>()`.
+///
+/// # Examples
+/// ```
+/// use abomonation::{encode_pod, decode_pod};
+///
+/// let mut bytes = Vec::new();
+/// encode_pod(&[1u8, 2, 3, 4], &mut bytes).unwrap();
+/// encode_pod(&5u8, &mut bytes).unwrap();
+///
+/// let (array, rest) = decode_pod::<[u8; 4]>(&bytes).unwrap();
+/// assert_eq!(array, &[1, 2, 3, 4]);
+/// assert_eq!(rest, &[5]);
+/// ```
+#[inline]
+pub fn decode_pod<T: Pod>(bytes: &[u8]) -> Option<(&T, &[u8])> {
+    if bytes.len() < mem::size_of::<T>() || !aligned::<T>(bytes) {
+        None
+    }
+    else {
+        let (mine, rest) = bytes.split_at(mem::size_of::<T>());
+        // Safe because `mine` is sized and aligned for `T`, and all bit patterns are valid.
+        Some((unsafe { &*(mine.as_ptr() as *const T) }, rest))
+    }
+}
+
+/// Decodes all of a binary slice as a slice of plain data.
+///
+/// The result is `None` if the length of `bytes` is not a multiple of `mem::size_of::<T>()`
+/// or its start is not aligned to `mem::align_of::<T>()`. A zero-sized `T` decodes only
+/// from empty bytes, as an empty slice.
+#[inline]
+pub fn decode_pod_slice<T: Pod>(bytes: &[u8]) -> Option<&[T]> {
+    match bytes.len().checked_div(mem::size_of::<T>()) {
+        // zero-sized types
+        None => if bytes.is_empty() { Some(&[]) } else { None },
+        Some(count) => {
+            if count * mem::size_of::<T>() != bytes.len() || !aligned::<T>(bytes) { None }
+            else {
+                // Safe because `bytes` is sized and aligned for `[T]`, and all bit patterns are valid.
+                Some(unsafe { std::slice::from_raw_parts(bytes.as_ptr() as *const T, count) })
+            }
+        },
+    }
+}
+
+/// Indicates whether `bytes` starts at an address aligned for `T`.
+#[inline]
+fn aligned<T>(bytes: &[u8]) -> bool {
+    (bytes.as_ptr() as usize) & (mem::align_of::<T>() - 1) == 0
+}
@@ -0,0 +1,344 @@
+//! Shared pointers, whose targets are entombed once for each call to `encode`.
+//!
+//! Several `Rc<T>` or `Arc<T>` values may point at the same target. During `encode` (and
+//! `measure`) the addresses of targets are recorded as they are first entombed, and later
+//! pointers to the same target are written as a reference to that first occurrence. During
+//! `decode` the targets are recorded as they are exhumed, and each reference is pointed at
+//! the same region of the decoded bytes.
+//!
+//! Each target is written in the layout `Rc` and `Arc` use for their allocations: two counts
+//! followed by the value. The counts are set so large that dropping decoded pointers (or
+//! clones of them) can never bring them to zero, and so never frees decoded memory.
+//!
+//! A clone of a decoded pointer is an owned `Rc` or `Arc` with no lifetime tying it to the
+//! bytes, and nothing stops it from outliving them; reading through it afterwards reads freed
+//! memory. Callers of `decode` and `Abomonated::new` must ensure that no such clone outlives
+//! the bytes, including clones of elements handed out by `Abomonated::split_elements`.
+//! `Abomonated::from_value` refuses to encode shared pointers, so that its callers need only
+//! ensure that the implementations they encode with are correct.
+//!
+//! Shared pointers encoded outside of `encode` (by calling `entomb` directly) are written once
+//! per pointer, without deduplication, and decode correctly with `exhume`. Calls to `extent`
+//! made while entombing record targets in a table of their own, and do not disturb the
+//! deduplication of the targets being entombed.
+//!
+//! # Untrusted input
+//!
+//! The layout of the allocations of `Rc` and `Arc` is private to the standard library, and
+//! decoded pointers are created with `from_raw` from pointers that never came from `into_raw`.
+//! The layout is checked once against pointers built by the standard library, and encoding or
+//! decoding panics should it differ; nothing beyond that check supports these conversions.
+//! Each reference to an earlier target is checked against the type and layout of the pointer
+//! it decodes as, so corrupt references fail to decode rather than reinterpret one target as
+//! another type. Even so, decoding shared pointers from untrusted input is not supported.
+
+use std::alloc::Layout;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::io::{Read, Write};
+use std::io::Result as IOResult;
+use std::mem;
+use std::rc::Rc;
+use std::sync::{Arc, OnceLock};
+
+use Abomonation;
+use limits;
+use padding;
+
+/// The strong and weak counts of decoded targets.
+///
+/// This is large enough that no sequence of drops reaches zero, and small enough that clones
+/// do not trip the overflow checks of `Rc` and `Arc`.
+const DECODED_COUNT: usize = usize::MAX >> 2;
+
+/// Shared targets seen by the `encode`, `measure`, or `decode` call in progress.
+#[derive(Default)]
+pub(crate) struct Tables {
+    /// Set while a call is in progress; otherwise targets are not deduplicated.
+    active: bool,
+    /// The addresses of targets entombed so far, with their order of appearance.
+    entombed: Option<HashMap<usize, usize>>,
+    /// The addresses of targets measured so far, with their order of appearance.
+    measured: Option<HashMap<usize, usize>>,
+    /// The targets exhumed so far, in order of appearance.
+    exhumed: Vec<Exhumed>,
+}
+
+/// The address of an exhumed target, with the layout and type of the pointer it decoded as.
+struct Exhumed {
+    target: usize,
+    value: Layout,
+    type_name: &'static str,
+}
+
+thread_local!(static TABLES: RefCell<Tables> = RefCell::new(Tables::default()));
+
+/// Fresh tables for the duration of an `encode`, `measure`, or `decode` call.
+///
+/// Dropping the scope restores the tables of any enclosing call.
+pub(crate) struct Scope {
+    previous: Tables,
+}
+
+impl Scope {
+    #[inline]
+    pub(crate) fn enter() -> Self {
+        Scope { previous: TABLES.with(|cell| cell.replace(Tables::active())) }
+    }
+}
+
+impl Drop for Scope {
+    #[inline]
+    fn drop(&mut self) {
+        let previous = mem::take(&mut self.previous);
+        TABLES.with(|cell| *cell.borrow_mut() = previous);
+    }
+}
+
+impl Tables {
+    /// Empty tables, which record targets as they are seen.
+    pub(crate) fn active() -> Self {
+        Tables { active: true, ..Tables::default() }
+    }
+}
+
+/// Retained tables for the duration of one of several calls, such as the pushes of a
+/// `VecEncoder`, so that targets are deduplicated across all of them.
+///
+/// Dropping the scope moves the tables back, and restores the tables of any enclosing call.
+pub(crate) struct Resumed<'a> {
+    tables: &'a mut Tables,
+}
+
+impl<'a> Resumed<'a> {
+    #[inline]
+    pub(crate) fn enter(tables: &'a mut Tables) -> Self {
+        TABLES.with(|cell| mem::swap(&mut *cell.borrow_mut(), tables));
+        Resumed { tables }
+    }
+}
+
+impl<'a> Drop for Resumed<'a> {
+    #[inline]
+    fn drop(&mut self) {
+        TABLES.with(|cell| mem::swap(&mut *cell.borrow_mut(), self.tables));
+    }
+}
+
+/// Reports the order of appearance of an entombed target, or records it as a new target.
+fn entombed(target: *const u8) -> Option<usize> {
+    seen(target, |tables| &mut tables.entombed)
+}
+
+/// Reports the order of appearance of a measured target, or records it as a new target.
+fn measured(target: *const u8) -> Option<usize> {
+    seen(target, |tables| &mut tables.measured)
+}
+
+/// Reports the order of appearance of a target in `table`, or records it as a new target.
+fn seen<F>(target: *const u8, table: F) -> Option<usize>
+where
+    F: FnOnce(&mut Tables) -> &mut Option<HashMap<usize, usize>>,
+{
+    TABLES.with(|cell| {
+        let mut tables = cell.borrow_mut();
+        if !tables.active { return None; }
+        let table = table(&mut tables).get_or_insert_with(HashMap::new);
+        let next = table.len();
+        match table.entry(target as usize) {
+            Entry::Occupied(entry) => Some(*entry.get()),
+            Entry::Vacant(entry) => { entry.insert(next); None },
+        }
+    })
+}
+
+/// The layout of the counts preceding each target.
+#[inline]
+fn header() -> Layout {
+    Layout::new::<[usize; 2]>()
+}
+
+/// Asserts that `Rc` and `Arc` place their strong and weak counts just before their values.
+///
+/// The check is made once, with pointers whose counts are known, and panics should the
+/// standard library lay out its allocations otherwise.
+fn check_layout() {
+    static CHECKED: OnceLock<()> = OnceLock::new();
+    CHECKED.get_or_init(|| {
+        let rc = Rc::new(0u64);
+        let (_rc_clones, _rc_weak) = ([rc.clone(), rc.clone()], Rc::downgrade(&rc));
+        let arc = Arc::new(0u64);
+        let (_arc_clones, _arc_weak) = ([arc.clone(), arc.clone()], Arc::downgrade(&arc));
+        // three strong references, and two weak ones counting the one all strong ones share.
+        let counts = |value: *const u64| unsafe { (value as *const [usize; 2]).sub(1).read() };
+        assert!(counts(Rc::as_ptr(&rc)) == [3, 2], "unsupported Rc layout");
+        assert!(counts(Arc::as_ptr(&arc)) == [3, 2], "unsupported Arc layout");
+    });
+}
+
+/// Entombs a shared target, or a reference to it if it has already been entombed.
+///
+/// Each pointer is written as a `usize` tag, which is zero for a new target and otherwise one
+/// more than the order of appearance of the target. New targets follow their tag, laid out as
+/// `Rc` and `Arc` lay out their allocations, and then any data the target owns.
+unsafe fn entomb_target<W: Write, F>(target: *const u8, value: Layout, write: &mut W, entomb: F) -> IOResult<()>
+where
+    F: FnOnce(&mut W) -> IOResult<()>,
+{
+    check_layout();
+    if padding::active() {
+        return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, "shared pointers cannot be owned by Abomonated::from_value"));
+    }
+    match entombed(target) {
+        Some(index) => write.write_all(&(index + 1).to_ne_bytes()),
+        None => {
+            let (_, offset) = header().extend(value).expect("shared target too large");
+            write.write_all(&0usize.to_ne_bytes())?;
+            write.write_all(&DECODED_COUNT.to_ne_bytes())?;
+            write.write_all(&DECODED_COUNT.to_ne_bytes())?;
+            std::io::copy(&mut std::io::repeat(0).take((offset - header().size()) as u64), write)?;
+            write.write_all(std::slice::from_raw_parts(target, value.size()))?;
+            entomb(write)
+        }
+    }
+}
+
+/// Exhumes a shared target, or resolves a reference to a previously exhumed target.
+///
+/// The result is a pointer to the target's value, and the remaining bytes. A reference must be
+/// to a target exhumed as a pointer of type `S` with a value of layout `value`.
+unsafe fn exhume_target<'b, S: ?Sized, F>(value: Layout, bytes: &'b mut [u8], exhume: F) -> Option<(*mut u8, &'b mut [u8])>
+where
+    F: FnOnce(*mut u8, &'b mut [u8]) -> Option<&'b mut [u8]>,
+{
+    check_layout();
+    let type_name = std::any::type_name::<S>();
+    if bytes.len() < mem::size_of::<usize>() { limits::short(bytes, mem::size_of::<usize>()); return None; }
+    let (tag, rest) = bytes.split_at_mut(mem::size_of::<usize>());
+    let tag = (tag.as_ptr() as *const usize).read_unaligned();
+    if tag > 0 {
+        let target = TABLES.with(|cell| {
+            let tables = cell.borrow();
+            let exhumed = tables.exhumed.get(tag - 1)?;
+            if exhumed.value == value && exhumed.type_name == type_name { Some(exhumed.target) } else { None }
+        })?;
+        Some((target as *mut u8, rest))
+    }
+    else {
+        let (_, offset) = header().extend(value).ok()?;
+        let binary_len = offset.checked_add(value.size())?;
+        if binary_len > rest.len() { limits::short(rest, binary_len); return None; }
+        let (mine, rest) = rest.split_at_mut(binary_len);
+        let target = mine.as_mut_ptr().add(offset);
+        TABLES.with(|cell| {
+            let mut tables = cell.borrow_mut();
+            if tables.active { tables.exhumed.push(Exhumed { target: target as usize, value, type_name }); }
+        });
+        let rest = exhume(target, rest)?;
+        Some((target, rest))
+    }
+}
+
+/// Reports the number of bytes `entomb_target` would write.
+fn extent_target<F: FnOnce() -> usize>(target: *const u8, value: Layout, extent: F) -> usize {
+    match measured(target) {
+        Some(_) => mem::size_of::<usize>(),
+        None => {
+            let (_, offset) = header().extend(value).expect("shared target too large");
+            mem::size_of::<usize>() + offset + value.size() + extent()
+        }
+    }
+}
+
+// Implementations for `Rc` and `Arc` of sized types, slices, and strings.
+macro_rules! shared_abomonate {
+    ($shared:ident) => (
+        impl<T: Abomonation> Abomonation for $shared<T> {
+            #[inline]
+            unsafe fn entomb<W: Write>(&self, write: &mut W) -> IOResult<()> {
+                entomb_target($shared::as_ptr(self) as *const u8, Layout::new::<T>(), write, |write| (**self).entomb(write))
+            }
+            #[inline]
+            unsafe fn exhume<'b>(&mut self, bytes: &'b mut [u8]) -> Option<&'b mut [u8]> {
+                let (target, rest) = exhume_target::<Self, _>(Layout::new::<T>(), bytes, |target, rest| {
+                    if T::HAS_INDIRECTION { (*(target as *mut T)).exhume(rest) } else { Some(rest) }
+                })?;
+                std::ptr::write(self, $shared::from_raw(target as *const T));
+                Some(rest)
+            }
+            #[inline]
+            fn extent(&self) -> usize {
+                extent_target($shared::as_ptr(self) as *const u8, Layout::new::<T>(), || (**self).extent())
+            }
+        }
+
+        impl<T: Abomonation> Abomonation for $shared<[T]> {
+            #[inline]
+            unsafe fn entomb<W: Write>(&self, write: &mut W) -> IOResult<()> {
+                let target = $shared::as_ptr(self) as *const u8;
+                entomb_target(target, Layout::for_value(&**self), write, |write| {
+                    if T::HAS_INDIRECTION {
+                        for element in self.iter() { element.entomb(write)?; }
+                    }
+                    Ok(())
+                })
+            }
+            #[inline]
+            unsafe fn exhume<'b>(&mut self, bytes: &'b mut [u8]) -> Option<&'b mut [u8]> {
+                // the length is in the fat pointer; avoid referencing the invalid pointee.
+                let length = mem::transmute_copy::<$shared<[T]>, *const [T]>(self).len();
+                if !limits::elements(length) { return None; }
+                let (target, rest) = exhume_target::<Self, _>(Layout::array::<T>(length).ok()?, bytes, |target, mut rest| {
+                    if T::HAS_INDIRECTION {
+                        let depth = limits::descend()?;
+                        for element in std::slice::from_raw_parts_mut(target as *mut T, length) {
+                            let temp = rest;
+                            match element.exhume(temp) {
+                                Some(temp) => rest = temp,
+                                None => { limits::ascend(depth); return None; },
+                            }
+                        }
+                        limits::ascend(depth);
+                    }
+                    Some(rest)
+                })?;
+                std::ptr::write(self, $shared::from_raw(std::ptr::slice_from_raw_parts(target as *const T, length)));
+                Some(rest)
+            }
+            #[inline]
+            fn extent(&self) -> usize {
+                let target = $shared::as_ptr(self) as *const u8;
+                extent_target(target, Layout::for_value(&**self), || {
+                    let mut sum = 0;
+                    if T::HAS_INDIRECTION {
+                        for element in self.iter() { sum += element.extent(); }
+                    }
+                    sum
+                })
+            }
+        }
+
+        impl Abomonation for $shared<str> {
+            #[inline]
+            unsafe fn entomb<W: Write>(&self, write: &mut W) -> IOResult<()> {
+                let target = $shared::as_ptr(self) as *const u8;
+                entomb_target(target, Layout::for_value(&**self), write, |_| Ok(()))
+            }
+            #[inline]
+            unsafe fn exhume<'b>(&mut self, bytes: &'b mut [u8]) -> Option<&'b mut [u8]> {
+                let length = mem::transmute_copy::<$shared<str>, *const [u8]>(self).len();
+                let (target, rest) = exhume_target::<Self, _>(Layout::array::<u8>(length).ok()?, bytes, |_, rest| Some(rest))?;
+                std::ptr::write(self, $shared::from_raw(std::ptr::slice_from_raw_parts(target, length) as *const str));
+                Some(rest)
+            }
+            #[inline]
+            fn extent(&self) -> usize {
+                extent_target($shared::as_ptr(self) as *const u8, Layout::for_value(&**self), || 0)
+            }
+        }
+    );
+}
+
+shared_abomonate!(Rc);
+shared_abomonate!(Arc);
@@ -0,0 +1,143 @@
+//! Detection of decoded memory reaching the allocator.
+//!
+//! Decoded `Vec`, `String` and `Box` values point into the bytes they were decoded
+//! from. Should one of them be dropped or grown, perhaps through an incorrect
+//! `exhume` implementation or a `mem::replace` through a mutable reference, the
+//! allocator would be asked to free or resize memory it never allocated.
+//!
+//! With the `sanitize` feature enabled, `decode` records the range of bytes each
+//! call consumed. The `Sanitizer` allocator wraps another allocator and checks
+//! each deallocation and reallocation against these ranges, aborting the process
+//! with a message identifying the problem rather than corrupting the heap.
+//! Global allocators may not unwind, which is why this aborts rather than panics.
+//!
+//! # Examples
+//! ```ignore
+//! use std::alloc::System;
+//! use abomonation::sanitize::Sanitizer;
+//!
+//! #[global_allocator]
+//! static ALLOCATOR: Sanitizer<System> = Sanitizer::new(System);
+//! ```
+
+use std::alloc::{GlobalAlloc, Layout};
+use std::cell::UnsafeCell;
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// The number of decoded regions tracked at once; the oldest are forgotten first.
+const CAPACITY: usize = 4096;
+
+/// An allocator which detects attempts to free or resize decoded memory.
+pub struct Sanitizer<A> {
+    inner: A,
+}
+
+impl<A> Sanitizer<A> {
+    /// Wraps `inner`, to which all allocation requests are forwarded.
+    pub const fn new(inner: A) -> Self {
+        Sanitizer { inner }
+    }
+}
+
+unsafe impl<A: GlobalAlloc> GlobalAlloc for Sanitizer<A> {
+    #[inline]
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        self.inner.alloc(layout)
+    }
+    #[inline]
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        self.inner.alloc_zeroed(layout)
+    }
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        release(ptr as usize, layout.size(), "dropped");
+        self.inner.dealloc(ptr, layout)
+    }
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        release(ptr as usize, layout.size(), "resized");
+        self.inner.realloc(ptr, layout, new_size)
+    }
+}
+
+/// Records that `[lower, upper)` now backs decoded data.
+pub(crate) fn register(lower: usize, upper: usize) {
+    if lower < upper {
+        let mut regions = REGIONS.lock();
+        let next = regions.next;
+        regions.ranges[next % CAPACITY] = (lower, upper);
+        regions.next = next.wrapping_add(1);
+    }
+}
+
+/// Checks that the allocation at `ptr` is not backed by decoded bytes, and forgets
+/// any regions overlapping it.
+///
+/// An allocation holding decoded bytes starts at or before the first region it
+/// contains, whereas decoded data always start strictly within their region.
+fn release(ptr: usize, size: usize, action: &str) {
+    let mut regions = REGIONS.lock();
+    if let Some(range) = regions.ranges.iter().find(|range| range.0 < ptr && ptr < range.1) {
+        let _ = writeln!(
+            std::io::stderr(),
+            "abomonation: memory at {:#x} backed by decoded bytes [{:#x}, {:#x}) was {}; \
+             decoded Vec, String, and Box values must not be dropped or grown.",
+            ptr, range.0, range.1, action,
+        );
+        std::process::abort();
+    }
+    for range in regions.ranges.iter_mut() {
+        if range.0 < ptr + size.max(1) && ptr < range.1 {
+            *range = (0, 0);
+        }
+    }
+}
+
+struct Regions {
+    ranges: [(usize, usize); CAPACITY],
+    next: usize,
+}
+
+/// A spin lock, as the allocator must not itself allocate.
+struct Lock {
+    locked: AtomicBool,
+    regions: UnsafeCell<Regions>,
+}
+
+unsafe impl Sync for Lock { }
+
+impl Lock {
+    fn lock(&self) -> Guard<'_> {
+        while self.locked.compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed).is_err() {
+            std::hint::spin_loop();
+        }
+        Guard { lock: self }
+    }
+}
+
+struct Guard<'a> {
+    lock: &'a Lock,
+}
+
+impl<'a> std::ops::Deref for Guard<'a> {
+    type Target = Regions;
+    fn deref(&self) -> &Regions {
+        unsafe { &*self.lock.regions.get() }
+    }
+}
+
+impl<'a> std::ops::DerefMut for Guard<'a> {
+    fn deref_mut(&mut self) -> &mut Regions {
+        unsafe { &mut *self.lock.regions.get() }
+    }
+}
+
+impl<'a> Drop for Guard<'a> {
+    fn drop(&mut self) {
+        self.lock.locked.store(false, Ordering::Release);
+    }
+}
+
+static REGIONS: Lock = Lock {
+    locked: AtomicBool::new(false),
+    regions: UnsafeCell::new(Regions { ranges: [(0, 0); CAPACITY], next: 0 }),
+};
@@ -0,0 +1,154 @@
+//! A timestamp with a stable binary layout.
+//!
+//! `SystemTime` implements `Abomonation` on unix platforms, but its layout differs between
+//! platforms and releases. `Timestamp` instead holds signed nanoseconds since the unix epoch
+//! as eight little-endian bytes, so its encoded form is the same on every platform, and needs
+//! no alignment. It covers the years 1677 through 2262.
+//!
+//! # Examples
+//! ```
+//! use std::time::{Duration, SystemTime};
+//! use abomonation::{encode, decode};
+//! use abomonation::time::Timestamp;
+//!
+//! let events = vec![(Timestamp::from_nanos(1_500_000_000_000_000_000), format!("start"))];
+//!
+//! let mut bytes = Vec::new();
+//! unsafe { encode(&events, &mut bytes).unwrap(); }
+//!
+//! if let Some((result, _)) = unsafe { decode::<Vec<(Timestamp, String)>>(&mut bytes) } {
+//!     let time = SystemTime::from(result[0].0);
+//!     assert_eq!(time, SystemTime::UNIX_EPOCH + Duration::from_secs(1_500_000_000));
+//! }
+//! ```
+
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+use std::time::{Duration, SystemTime};
+
+use Abomonation;
+use pod::Pod;
+
+/// A point in time, as signed nanoseconds since the unix epoch.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Default)]
+#[repr(transparent)]
+pub struct Timestamp {
+    /// Nanoseconds since the unix epoch, as a little-endian `i64`.
+    nanos: [u8; 8],
+}
+
+impl Timestamp {
+    /// The unix epoch, 1970-01-01 00:00:00 UTC.
+    pub const UNIX_EPOCH: Timestamp = Timestamp::from_nanos(0);
+    /// The earliest representable timestamp.
+    pub const MIN: Timestamp = Timestamp::from_nanos(i64::MIN);
+    /// The latest representable timestamp.
+    pub const MAX: Timestamp = Timestamp::from_nanos(i64::MAX);
+
+    /// The timestamp `nanos` nanoseconds after (or, if negative, before) the unix epoch.
+    #[inline]
+    pub const fn from_nanos(nanos: i64) -> Self {
+        Timestamp { nanos: nanos.to_le_bytes() }
+    }
+
+    /// The number of nanoseconds since the unix epoch, negative for earlier times.
+    #[inline]
+    pub const fn as_nanos(&self) -> i64 {
+        i64::from_le_bytes(self.nanos)
+    }
+
+    /// The current system time.
+    ///
+    /// # Panics
+    /// Panics if the system clock is outside the representable range.
+    pub fn now() -> Self {
+        Timestamp::try_from(SystemTime::now()).expect("system time out of range")
+    }
+
+    /// The timestamp `duration` after this one, or `None` on overflow.
+    #[inline]
+    pub fn checked_add(&self, duration: Duration) -> Option<Self> {
+        let nanos = i64::try_from(duration.as_nanos()).ok()?;
+        self.as_nanos().checked_add(nanos).map(Timestamp::from_nanos)
+    }
+
+    /// The timestamp `duration` before this one, or `None` on overflow.
+    #[inline]
+    pub fn checked_sub(&self, duration: Duration) -> Option<Self> {
+        let nanos = i64::try_from(duration.as_nanos()).ok()?;
+        self.as_nanos().checked_sub(nanos).map(Timestamp::from_nanos)
+    }
+
+    /// The time elapsed from `earlier` to this timestamp, or `None` if `earlier` is later.
+    #[inline]
+    pub fn duration_since(&self, earlier: Timestamp) -> Option<Duration> {
+        let nanos = (self.as_nanos() as i128) - (earlier.as_nanos() as i128);
+        if nanos < 0 { None } else { Some(Duration::from_nanos(nanos as u64)) }
+    }
+}
+
+// Ordered by time, rather than by the little-endian bytes.
+impl PartialOrd for Timestamp {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> { Some(self.cmp(other)) }
+}
+
+impl Ord for Timestamp {
+    fn cmp(&self, other: &Self) -> Ordering { self.as_nanos().cmp(&other.as_nanos()) }
+}
+
+impl Abomonation for Timestamp { const HAS_INDIRECTION: bool = false; }
+
+unsafe impl Pod for Timestamp { }
+
+/// The error from converting a time outside the range of `Timestamp`, or a `Timestamp`
+/// before the unix epoch into a `Duration`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct OutOfRange;
+
+impl fmt::Display for OutOfRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("time out of range for conversion")
+    }
+}
+
+impl Error for OutOfRange { }
+
+impl TryFrom<SystemTime> for Timestamp {
+    type Error = OutOfRange;
+    fn try_from(time: SystemTime) -> Result<Self, OutOfRange> {
+        let nanos = match time.duration_since(SystemTime::UNIX_EPOCH) {
+            Ok(after) => i64::try_from(after.as_nanos()).map_err(|_| OutOfRange)?,
+            Err(before) => {
+                let before = i128::try_from(before.duration().as_nanos()).map_err(|_| OutOfRange)?;
+                i64::try_from(-before).map_err(|_| OutOfRange)?
+            },
+        };
+        Ok(Timestamp::from_nanos(nanos))
+    }
+}
+
+impl From<Timestamp> for SystemTime {
+    fn from(timestamp: Timestamp) -> Self {
+        let nanos = timestamp.as_nanos();
+        let magnitude = Duration::from_nanos(nanos.unsigned_abs());
+        if nanos < 0 { SystemTime::UNIX_EPOCH - magnitude } else { SystemTime::UNIX_EPOCH + magnitude }
+    }
+}
+
+/// Interprets a duration as the time elapsed since the unix epoch.
+impl TryFrom<Duration> for Timestamp {
+    type Error = OutOfRange;
+    fn try_from(since_epoch: Duration) -> Result<Self, OutOfRange> {
+        i64::try_from(since_epoch.as_nanos()).map(Timestamp::from_nanos).map_err(|_| OutOfRange)
+    }
+}
+
+/// The time elapsed since the unix epoch, which fails for earlier timestamps.
+impl TryFrom<Timestamp> for Duration {
+    type Error = OutOfRange;
+    fn try_from(timestamp: Timestamp) -> Result<Self, OutOfRange> {
+        u64::try_from(timestamp.as_nanos()).map(Duration::from_nanos).map_err(|_| OutOfRange)
+    }
+}
@@ -0,0 +1,384 @@
+//! Borrowed views of decoded data.
+//!
+//! `decode` returns a `&T`, whose `String` and `Vec` fields look owned but are backed by the
+//! decoded bytes. `decode_view` instead returns a `T::View<'a>`, which exposes the same data
+//! as standard borrowed types with the lifetime of the bytes: `String` as `&'a str`, `Vec<T>`
+//! as a `Slice<'a, T>` of the views of its elements, `Option<T>`, `Result<T, E>`, ranges and
+//! tuples component-wise, and plain values by copy. Types with `#[derive(Viewable)]` or
+//! `unsafe_abomonate!` are viewed as `&'a Self`, as are arrays, atomics and the collections of
+//! this crate; other types may implement `Viewable` the same way.
+//!
+//! Sequences are viewed as a `Slice<'a, T>` rather than as a `&'a [T::View<'a>]`, as views are
+//! computed rather than stored, and a slice of them would need to be allocated. A `Slice` views
+//! each element as it is accessed, and `Slice::as_slice` returns the decoded `&'a [T]` itself.
+//!
+//! # Examples
+//! ```
+//! use abomonation::{encode, decode_view};
+//!
+//! let vector = (0 .. 256u64).map(|i| (i, format!("{}", i))).collect::<Vec<_>>();
+//!
+//! let mut bytes = Vec::new();
+//! unsafe { encode(&vector, &mut bytes).unwrap(); }
+//!
+//! if let Some((view, remaining)) = unsafe { decode_view::<Vec<(u64, String)>>(&mut bytes) } {
+//!     let (number, name): (u64, &str) = view.get(17).unwrap();
+//!     assert_eq!((number, name), (17, "17"));
+//!     assert!(view.iter().map(|(_, name)| name.len()).eq(vector.iter().map(|(_, name)| name.len())));
+//!     assert!(remaining.len() == 0);
+//! }
+//! ```
+
+use std::borrow::Cow;
+use std::cell::Cell;
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, VecDeque};
+use std::ffi::{CStr, CString};
+use std::fmt;
+use std::marker::PhantomData;
+use std::net::{SocketAddr, SocketAddrV4, SocketAddrV6, IpAddr, Ipv4Addr, Ipv6Addr};
+use std::num::*;
+use std::ops::{Bound, Range, RangeFrom, RangeFull, RangeInclusive, RangeTo, RangeToInclusive};
+use std::rc::Rc;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicI8, AtomicI16, AtomicI32, AtomicI64, AtomicIsize};
+use std::sync::atomic::{AtomicU8, AtomicU16, AtomicU32, AtomicU64, AtomicUsize};
+use std::time::Duration;
+
+use {Abomonation, decode};
+use limits::{DecodeLimits, DecodeError, decode_with_limits};
+use flat::{FlatMap, FlatSet};
+use frozen::{FrozenHashMap, FrozenBTreeMap};
+use time::Timestamp;
+
+/// Types with a borrowed view of their decoded form.
+pub trait Viewable: Abomonation {
+    /// The borrowed representation of a decoded value, valid for `'a`.
+    type View<'a> where Self: 'a;
+    /// Views a decoded value.
+    fn view(&self) -> Self::View<'_>;
+}
+
+/// Decodes a mutable binary slice into a borrowed view of the decoded value.
+///
+/// This decodes as `decode` does, and views the result with the lifetime of `bytes`.
+///
+/// # Safety
+///
+/// This method is unsafe for the same reasons as `decode`.
+#[inline]
+pub unsafe fn decode_view<T: Viewable>(bytes: &mut [u8]) -> Option<(T::View<'_>, &mut [u8])> {
+    let (decoded, remaining) = decode::<T>(bytes)?;
+    Some((decoded.view(), remaining))
+}
+
+/// Decodes a mutable binary slice into a borrowed view of the decoded value, within `limits`.
+///
+/// This decodes as `decode_with_limits` does, and views the result with the lifetime of `bytes`.
+///
+/// # Safety
+///
+/// This method is unsafe for the same reasons as `decode`.
+#[inline]
+pub unsafe fn decode_view_with_limits<'a, T: Viewable>(bytes: &'a mut [u8], limits: &DecodeLimits) -> Result<(T::View<'a>, &'a mut [u8]), DecodeError> {
+    let (decoded, remaining) = decode_with_limits::<T>(bytes, limits)?;
+    Ok((decoded.view(), remaining))
+}
+
+/// Implements `Viewable` for types that implement `Abomonation`, viewing them as `&'a Self`.
+///
+/// This is done for types with `unsafe_abomonate!`, as `#[derive(Viewable)]` does for derived
+/// types, so that they may appear within views, for example as the elements of a `Slice`.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! viewable_identity {
+    ($($t:ty),*) => {
+        $(
+            impl $crate::Viewable for $t {
+                type View<'a> = &'a $t where $t: 'a;
+                #[inline] fn view(&self) -> &$t { self }
+            }
+        )*
+    };
+}
+
+/// A view of a decoded sequence, whose elements are viewed as they are accessed.
+pub struct Slice<'a, T: 'a> {
+    slice: &'a [T],
+}
+
+impl<'a, T: Viewable> Slice<'a, T> {
+    /// The number of elements.
+    #[inline] pub fn len(&self) -> usize { self.slice.len() }
+    /// Indicates whether there are no elements.
+    #[inline] pub fn is_empty(&self) -> bool { self.slice.is_empty() }
+    /// A view of the element at `index`, if any.
+    #[inline] pub fn get(&self, index: usize) -> Option<T::View<'a>> { self.slice.get(index).map(|element| element.view()) }
+    /// Iterates over views of the elements.
+    #[inline] pub fn iter(&self) -> impl DoubleEndedIterator<Item=T::View<'a>> + ExactSizeIterator + 'a { self.slice.iter().map(|element| element.view()) }
+    /// The decoded elements themselves.
+    #[inline] pub fn as_slice(&self) -> &'a [T] { self.slice }
+}
+
+impl<'a, T> Clone for Slice<'a, T> {
+    fn clone(&self) -> Self { *self }
+}
+
+impl<'a, T> Copy for Slice<'a, T> { }
+
+impl<'a, T: fmt::Debug> fmt::Debug for Slice<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.slice.fmt(f)
+    }
+}
+
+// Plain values are viewed by copy.
+macro_rules! copy_viewable {
+    ($($t:ty),*) => {
+        $(
+            impl Viewable for $t {
+                type View<'a> = $t;
+                #[inline] fn view(&self) -> $t { *self }
+            }
+        )*
+    };
+}
+
+copy_viewable!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+copy_viewable!(NonZeroU8, NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU128, NonZeroUsize);
+copy_viewable!(NonZeroI8, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI128, NonZeroIsize);
+copy_viewable!(f32, f64, bool, char, (), Duration, Timestamp, Ordering, RangeFull);
+copy_viewable!(IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6);
+
+#[cfg(unix)]
+copy_viewable!(::std::time::SystemTime);
+
+impl<T> Viewable for PhantomData<T> {
+    type View<'a> = PhantomData<T> where T: 'a;
+    #[inline] fn view(&self) -> PhantomData<T> { PhantomData }
+}
+
+// Values that may change after decoding, and types whose fields are private, are viewed whole.
+macro_rules! reference_viewable {
+    ($($t:ty),*) => {
+        $(
+            impl Viewable for $t {
+                type View<'a> = &'a $t;
+                #[inline] fn view(&self) -> &$t { self }
+            }
+        )*
+    };
+}
+
+reference_viewable!(AtomicBool, AtomicU8, AtomicU16, AtomicU32, AtomicU64, AtomicUsize);
+reference_viewable!(AtomicI8, AtomicI16, AtomicI32, AtomicI64, AtomicIsize);
+
+impl<T: Abomonation + Copy> Viewable for Cell<T> {
+    type View<'a> = &'a Cell<T> where T: 'a;
+    #[inline] fn view(&self) -> &Cell<T> { self }
+}
+
+// The iteration state of `RangeInclusive` is private, and not reflected by its bounds alone.
+impl<T: Abomonation> Viewable for RangeInclusive<T> {
+    type View<'a> = &'a RangeInclusive<T> where T: 'a;
+    #[inline] fn view(&self) -> &RangeInclusive<T> { self }
+}
+
+impl<K: Abomonation, V: Abomonation> Viewable for FlatMap<K, V> {
+    type View<'a> = &'a FlatMap<K, V> where K: 'a, V: 'a;
+    #[inline] fn view(&self) -> &FlatMap<K, V> { self }
+}
+
+impl<T: Abomonation> Viewable for FlatSet<T> {
+    type View<'a> = &'a FlatSet<T> where T: 'a;
+    #[inline] fn view(&self) -> &FlatSet<T> { self }
+}
+
+impl<K: Abomonation, V: Abomonation> Viewable for FrozenHashMap<K, V> {
+    type View<'a> = &'a FrozenHashMap<K, V> where K: 'a, V: 'a;
+    #[inline] fn view(&self) -> &FrozenHashMap<K, V> { self }
+}
+
+impl<K: Abomonation, V: Abomonation> Viewable for FrozenBTreeMap<K, V> {
+    type View<'a> = &'a FrozenBTreeMap<K, V> where K: 'a, V: 'a;
+    #[inline] fn view(&self) -> &FrozenBTreeMap<K, V> { self }
+}
+
+impl Viewable for String {
+    type View<'a> = &'a str;
+    #[inline] fn view(&self) -> &str { self }
+}
+
+impl Viewable for Box<str> {
+    type View<'a> = &'a str;
+    #[inline] fn view(&self) -> &str { self }
+}
+
+impl<T: Viewable> Viewable for Vec<T> {
+    type View<'a> = Slice<'a, T> where T: 'a;
+    #[inline] fn view(&self) -> Slice<'_, T> { Slice { slice: &self[..] } }
+}
+
+impl<T: Viewable> Viewable for Box<[T]> {
+    type View<'a> = Slice<'a, T> where T: 'a;
+    #[inline] fn view(&self) -> Slice<'_, T> { Slice { slice: &self[..] } }
+}
+
+impl<T: Viewable> Viewable for Box<T> {
+    type View<'a> = T::View<'a> where T: 'a;
+    #[inline] fn view(&self) -> T::View<'_> { (**self).view() }
+}
+
+impl<T: Viewable> Viewable for Option<T> {
+    type View<'a> = Option<T::View<'a>> where T: 'a;
+    #[inline] fn view(&self) -> Option<T::View<'_>> { self.as_ref().map(|inner| inner.view()) }
+}
+
+impl<T: Viewable, E: Viewable> Viewable for Result<T, E> {
+    type View<'a> = Result<T::View<'a>, E::View<'a>> where T: 'a, E: 'a;
+    #[inline] fn view(&self) -> Self::View<'_> {
+        match *self {
+            Ok(ref inner) => Ok(inner.view()),
+            Err(ref inner) => Err(inner.view()),
+        }
+    }
+}
+
+impl<T: Viewable> Viewable for Range<T> {
+    type View<'a> = Range<T::View<'a>> where T: 'a;
+    #[inline] fn view(&self) -> Self::View<'_> { self.start.view() .. self.end.view() }
+}
+
+impl<T: Viewable> Viewable for RangeFrom<T> {
+    type View<'a> = RangeFrom<T::View<'a>> where T: 'a;
+    #[inline] fn view(&self) -> Self::View<'_> { self.start.view() .. }
+}
+
+impl<T: Viewable> Viewable for RangeTo<T> {
+    type View<'a> = RangeTo<T::View<'a>> where T: 'a;
+    #[inline] fn view(&self) -> Self::View<'_> { .. self.end.view() }
+}
+
+impl<T: Viewable> Viewable for RangeToInclusive<T> {
+    type View<'a> = RangeToInclusive<T::View<'a>> where T: 'a;
+    #[inline] fn view(&self) -> Self::View<'_> { ..= self.end.view() }
+}
+
+impl<T: Viewable> Viewable for Bound<T> {
+    type View<'a> = Bound<T::View<'a>> where T: 'a;
+    #[inline] fn view(&self) -> Self::View<'_> {
+        match *self {
+            Bound::Included(ref inner) => Bound::Included(inner.view()),
+            Bound::Excluded(ref inner) => Bound::Excluded(inner.view()),
+            Bound::Unbounded => Bound::Unbounded,
+        }
+    }
+}
+
+// Wrappers are viewed as wrapped views of their contents.
+macro_rules! wrapper_viewable {
+    ($wrapper:ident) => {
+        impl<T: Viewable> Viewable for $wrapper<T> {
+            type View<'a> = $wrapper<T::View<'a>> where T: 'a;
+            #[inline] fn view(&self) -> Self::View<'_> { $wrapper(self.0.view()) }
+        }
+    };
+}
+
+wrapper_viewable!(Wrapping);
+wrapper_viewable!(Saturating);
+wrapper_viewable!(Reverse);
+
+// general code for tuples, viewing each component.
+macro_rules! tuple_viewable {
+    ( $($name:ident)+) => (
+        impl<$($name: Viewable),*> Viewable for ($($name,)*) {
+            type View<'a> = ($($name::View<'a>,)*) where $($name: 'a),*;
+            #[allow(non_snake_case)]
+            #[inline] fn view(&self) -> Self::View<'_> {
+                let ($(ref $name,)*) = *self;
+                ($($name.view(),)*)
+            }
+        }
+    );
+}
+
+tuple_viewable!(A);
+tuple_viewable!(A B);
+tuple_viewable!(A B C);
+tuple_viewable!(A B C D);
+tuple_viewable!(A B C D E);
+tuple_viewable!(A B C D E F);
+tuple_viewable!(A B C D E F G);
+tuple_viewable!(A B C D E F G H);
+
+impl Viewable for Cow<'static, str> {
+    type View<'a> = &'a str;
+    #[inline] fn view(&self) -> &str { self }
+}
+
+impl<T: Viewable + Clone> Viewable for Cow<'static, [T]> {
+    type View<'a> = Slice<'a, T>;
+    #[inline] fn view(&self) -> Slice<'_, T> { Slice { slice: &self[..] } }
+}
+
+// Deques are viewed as their two contiguous parts, in order, as `VecDeque::as_slices` returns.
+impl<T: Viewable> Viewable for VecDeque<T> {
+    type View<'a> = (Slice<'a, T>, Slice<'a, T>) where T: 'a;
+    #[inline] fn view(&self) -> Self::View<'_> {
+        let (front, back) = self.as_slices();
+        (Slice { slice: front }, Slice { slice: back })
+    }
+}
+
+// Elements are viewed in the order they are stored, which is not their sorted order.
+impl<T: Viewable + Ord> Viewable for BinaryHeap<T> {
+    type View<'a> = Slice<'a, T> where T: 'a;
+    #[inline] fn view(&self) -> Slice<'_, T> { Slice { slice: self.as_slice() } }
+}
+
+// Shared pointers are viewed as their targets, which decoded pointers may share.
+macro_rules! shared_viewable {
+    ($shared:ident) => {
+        impl<T: Viewable> Viewable for $shared<T> {
+            type View<'a> = T::View<'a> where T: 'a;
+            #[inline] fn view(&self) -> T::View<'_> { (**self).view() }
+        }
+
+        impl<T: Viewable> Viewable for $shared<[T]> {
+            type View<'a> = Slice<'a, T> where T: 'a;
+            #[inline] fn view(&self) -> Slice<'_, T> { Slice { slice: &self[..] } }
+        }
+    };
+}
+
+shared_viewable!(Rc);
+shared_viewable!(Arc);
+
+// Arrays are viewed whole, rather than as a `Slice`, to retain their length in the type.
+impl<T: Abomonation, const N: usize> Viewable for [T; N] {
+    type View<'a> = &'a [T; N] where T: 'a;
+    #[inline] fn view(&self) -> &[T; N] { self }
+}
+
+impl Viewable for CString {
+    type View<'a> = &'a CStr;
+    #[inline] fn view(&self) -> &CStr { self }
+}
+
+impl Viewable for Box<CStr> {
+    type View<'a> = &'a CStr;
+    #[inline] fn view(&self) -> &CStr { self }
+}
+
+#[cfg(unix)]
+impl Viewable for ::std::ffi::OsString {
+    type View<'a> = &'a ::std::ffi::OsStr;
+    #[inline] fn view(&self) -> &::std::ffi::OsStr { self }
+}
+
+#[cfg(unix)]
+impl Viewable for ::std::path::PathBuf {
+    type View<'a> = &'a ::std::path::Path;
+    #[inline] fn view(&self) -> &::std::path::Path { self }
+}
@@ -0,0 +1,76 @@
+#![cfg(feature = "sanitize")]
+
+extern crate abomonation;
+
+use std::alloc::System;
+use std::process::Command;
+
+use abomonation::*;
+use abomonation::sanitize::Sanitizer;
+
+#[global_allocator]
+static ALLOCATOR: Sanitizer<System> = Sanitizer::new(System);
+
+#[test] fn test_drop_string() { _test_detects("test_drop_string", "dropped", || {
+    let mut bytes = Vec::new();
+    unsafe { encode(&format!("grawwwwrr!"), &mut bytes).unwrap(); }
+    let (result, _) = unsafe { decode::<String>(&mut bytes) }.unwrap();
+    drop(unsafe { std::ptr::read(result) });
+}); }
+
+#[test] fn test_drop_box() { _test_detects("test_drop_box", "dropped", || {
+    let mut bytes = Vec::new();
+    unsafe { encode(&vec![Some(Box::new(0u64))], &mut bytes).unwrap(); }
+    let (result, _) = unsafe { decode::<Vec<Option<Box<u64>>>>(&mut bytes) }.unwrap();
+    drop(unsafe { std::ptr::read(&result[0]) });
+}); }
+
+#[test] fn test_drop_slice_string() { _test_detects("test_drop_slice_string", "dropped", || {
+    let mut bytes = Vec::new();
+    unsafe { encode_slice(&[format!("grawwwwrr!")], &mut bytes).unwrap(); }
+    let (result, _) = unsafe { decode_slice::<String>(&mut bytes) }.unwrap();
+    drop(unsafe { std::ptr::read(&result[0]) });
+}); }
+
+#[test] fn test_grow_vec() { _test_detects("test_grow_vec", "resized", || {
+    let mut bytes = Vec::new();
+    unsafe { encode(&vec![0u64; 16], &mut bytes).unwrap(); }
+    let (result, _) = unsafe { decode::<Vec<u64>>(&mut bytes) }.unwrap();
+    let mut vector = std::mem::ManuallyDrop::new(unsafe { std::ptr::read(result) });
+    vector.push(0);
+}); }
+
+#[test]
+fn test_release_bytes() {
+    // dropping and reallocating the decoded bytes themselves is fine.
+    for _ in 0 .. 16 {
+        let mut bytes = Vec::new();
+        unsafe { encode(&0u64, &mut bytes).unwrap(); }
+        unsafe { encode(&vec![format!("grawwwwrr!"); 32], &mut bytes).unwrap(); }
+        {
+            let (_, rest) = unsafe { decode::<u64>(&mut bytes) }.unwrap();
+            let (result, _) = unsafe { decode::<Vec<String>>(rest) }.unwrap();
+            assert!(result == &vec![format!("grawwwwrr!"); 32]);
+        }
+        bytes.reserve(1 << 20);
+        drop(bytes);
+    }
+}
+
+/// Runs `logic` in a child process, and checks that it aborts with a message.
+fn _test_detects<F: FnOnce()>(name: &str, action: &str, logic: F) {
+    if std::env::var("ABOMONATION_SANITIZE_TEST").ok().as_ref().map(|x| &x[..]) == Some(name) {
+        logic();
+    }
+    else {
+        let output = Command::new(std::env::current_exe().unwrap())
+            .args(["--exact", name, "--test-threads=1"])
+            .env("ABOMONATION_SANITIZE_TEST", name)
+            .output()
+            .unwrap();
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("backed by decoded bytes"), "{}", stderr);
+        assert!(stderr.contains(action), "{}", stderr);
+    }
+}
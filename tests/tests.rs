@@ -14,11 +14,53 @@ use abomonation::*;
 #[test] fn test_u128_pass() { _test_pass(vec![0u128; 1024]); }
 #[test] fn test_string_pass() { _test_pass(vec![format!("grawwwwrr!"); 1024]); }
 #[test] fn test_vec_u_s_pass() { _test_pass(vec![vec![(0u64, format!("grawwwwrr!")); 32]; 32]); }
+#[test] fn test_box_slice_pass() { _test_pass(vec![vec![0u64; 32].into_boxed_slice(); 32]); }
+#[test] fn test_box_slice_s_pass() { _test_pass(vec![vec![format!("grawwwwrr!"); 32].into_boxed_slice(), Box::new([])]); }
+#[test] fn test_box_str_pass() { _test_pass(vec![format!("grawwwwrr!").into_boxed_str(); 1024]); }
+#[test] fn test_vec_deque_pass() { _test_pass(_vec_deque()); }
+#[test] fn test_binary_heap_pass() { _test_pass_heap(_binary_heap()); }
+#[test] fn test_frozen_hash_map_pass() { _test_pass(_frozen_hash_map()); }
+#[test] fn test_frozen_btree_map_pass() { _test_pass(_frozen_btree_map()); }
+#[test] fn test_frozen_empty_pass() { _test_pass(FrozenHashMap::<String, u64>::new()); }
+#[test] fn test_flat_map_pass() { _test_pass(_flat_map()); }
+#[test] fn test_flat_set_pass() { _test_pass(_flat_set()); }
+#[test] fn test_cstring_pass() { _test_pass(_cstrings()); }
+#[test] fn test_box_cstr_pass() { _test_pass(_box_cstrs()); }
+#[test] fn test_os_string_pass() { _test_pass(_os_strings()); }
+#[test] fn test_path_buf_pass() { _test_pass(_path_bufs()); }
+#[test] fn test_range_string_pass() { _test_pass(_ranges()); }
+#[test] fn test_range_inclusive_pass() { _test_pass(_ranges_inclusive()); }
+#[test] fn test_range_half_open_pass() { _test_pass((format!("grawwwwr") .., .. vec![0u64; 7], ..= format!("x"), ..)); }
+#[test] fn test_bound_pass() { _test_pass(_bounds()); }
+#[test] fn test_wrapper_pass() { _test_pass(_wrappers()); }
+#[test] fn test_ordering_pass() { _test_pass(vec![std::cmp::Ordering::Less, std::cmp::Ordering::Equal, std::cmp::Ordering::Greater]); }
+#[test] fn test_cow_str_pass() { _test_pass(_cow_strs()); }
+#[test] fn test_cow_slice_pass() { _test_pass(_cow_slices()); }
+#[test] fn test_times_pass() { _test_pass(_times()); }
 
 #[test] fn test_u64_fail() { _test_fail(vec![0u64; 1024]); }
 #[test] fn test_u128_fail() { _test_fail(vec![0u128; 1024]); }
 #[test] fn test_string_fail() { _test_fail(vec![format!("grawwwwrr!"); 1024]); }
 #[test] fn test_vec_u_s_fail() { _test_fail(vec![vec![(0u64, format!("grawwwwrr!")); 32]; 32]); }
+#[test] fn test_box_slice_s_fail() { _test_fail(vec![vec![format!("grawwwwrr!"); 32].into_boxed_slice(); 32]); }
+#[test] fn test_box_str_fail() { _test_fail(vec![format!("grawwwwrr!").into_boxed_str(); 1024]); }
+#[test] fn test_vec_deque_fail() { _test_fail(_vec_deque()); }
+#[test] fn test_binary_heap_fail() { _test_fail(_binary_heap()); }
+#[test] fn test_frozen_hash_map_fail() { _test_fail(_frozen_hash_map()); }
+#[test] fn test_frozen_btree_map_fail() { _test_fail(_frozen_btree_map()); }
+#[test] fn test_flat_map_fail() { _test_fail(_flat_map()); }
+#[test] fn test_flat_set_fail() { _test_fail(_flat_set()); }
+#[test] fn test_cstring_fail() { _test_fail(_cstrings()); }
+#[test] fn test_box_cstr_fail() { _test_fail(_box_cstrs()); }
+#[test] fn test_os_string_fail() { _test_fail(_os_strings()); }
+#[test] fn test_path_buf_fail() { _test_fail(_path_bufs()); }
+#[test] fn test_range_string_fail() { _test_fail(_ranges()); }
+#[test] fn test_range_inclusive_fail() { _test_fail(_ranges_inclusive()); }
+#[test] fn test_bound_fail() { _test_fail(_bounds()); }
+#[test] fn test_wrapper_fail() { _test_fail(_wrappers()); }
+#[test] fn test_cow_str_fail() { _test_fail(_cow_strs()); }
+#[test] fn test_cow_slice_fail() { _test_fail(_cow_slices()); }
+#[test] fn test_times_fail() { _test_fail(_times()); }
 
 #[test] fn test_array_size() { _test_size(vec![[0, 1, 2]; 1024]); }
 #[test] fn test_opt_vec_size() { _test_size(vec![Some(vec![0,1,2]), None]); }
@@ -30,6 +72,28 @@ use abomonation::*;
 #[test] fn test_u128_size() { _test_size(vec![0u128; 1024]); }
 #[test] fn test_string_size() { _test_size(vec![format!("grawwwwrr!"); 1024]); }
 #[test] fn test_vec_u_s_size() { _test_size(vec![vec![(0u64, format!("grawwwwrr!")); 32]; 32]); }
+#[test] fn test_box_slice_s_size() { _test_size(vec![vec![format!("grawwwwrr!"); 32].into_boxed_slice(); 32]); }
+#[test] fn test_box_str_size() { _test_size(vec![format!("grawwwwrr!").into_boxed_str(); 1024]); }
+#[test] fn test_vec_deque_size() { _test_size(_vec_deque()); }
+#[test] fn test_binary_heap_size() { _test_size(_binary_heap()); }
+#[test] fn test_frozen_hash_map_size() { _test_size(_frozen_hash_map()); }
+#[test] fn test_frozen_btree_map_size() { _test_size(_frozen_btree_map()); }
+#[test] fn test_flat_map_size() { _test_size(_flat_map()); }
+#[test] fn test_flat_set_size() { _test_size(_flat_set()); }
+#[test] fn test_cstring_size() { _test_size(_cstrings()); }
+#[test] fn test_box_cstr_size() { _test_size(_box_cstrs()); }
+#[test] fn test_os_string_size() { _test_size(_os_strings()); }
+#[test] fn test_path_buf_size() { _test_size(_path_bufs()); }
+#[test] fn test_range_string_size() { _test_size(_ranges()); }
+#[test] fn test_range_inclusive_size() { _test_size(_ranges_inclusive()); }
+#[test] fn test_range_half_open_size() { _test_size((format!("grawwwwrr!") .., .. vec![0u64; 7], ..= format!("x"), ..)); }
+#[test] fn test_bound_size() { _test_size(_bounds()); }
+#[test] fn test_wrapper_size() { _test_size(_wrappers()); }
+#[test] fn test_cow_str_size() { _test_size(_cow_strs()); }
+#[test] fn test_cow_slice_size() { _test_size(_cow_slices()); }
+#[test] fn test_times_size() { _test_size(_times()); }
+#[test] fn test_cells_pass() { _test_pass(vec![(std::cell::Cell::new(7u64), std::cell::Cell::new(Some((1u8, 'x')))); 32]); }
+#[test] fn test_atomics_size() { _test_size(_counters()); }
 
 #[test]
 fn test_phantom_data_for_non_abomonatable_type() {
@@ -38,6 +102,562 @@ fn test_phantom_data_for_non_abomonatable_type() {
     _test_pass(PhantomData::<NotAbomonatable>::default());
 }
 
+// a deque whose contents wrap around the end of its buffer.
+fn _vec_deque() -> std::collections::VecDeque<(u64, String)> {
+    let mut deque = std::collections::VecDeque::with_capacity(32);
+    for i in 0 .. 24 { deque.push_back((i, format!("{}", i))); }
+    for _ in 0 .. 16 { deque.pop_front(); }
+    for i in 24 .. 40 { deque.push_back((i, format!("{}", i))); }
+    deque
+}
+
+fn _binary_heap() -> std::collections::BinaryHeap<(u64, String)> {
+    (0 .. 256).map(|i| ((i * 7) % 31, format!("{}", i))).collect()
+}
+
+#[test]
+fn test_binary_heap_preserves_order() {
+    let heap = _binary_heap();
+    let mut bytes = Vec::new();
+    unsafe { encode(&heap, &mut bytes).unwrap(); }
+    let (result, _) = unsafe { decode::<std::collections::BinaryHeap<(u64, String)>>(&mut bytes[..]) }.unwrap();
+    assert_eq!(result.as_slice(), heap.as_slice());
+    assert_eq!(result.peek(), heap.peek());
+}
+
+#[test]
+fn test_binary_heap_decode_without_ord() {
+    use std::cmp::Ordering;
+    use std::sync::atomic::{AtomicUsize, Ordering as Atomic};
+    static COMPARISONS: AtomicUsize = AtomicUsize::new(0);
+    #[derive(PartialEq, Eq)]
+    struct Counted(u64);
+    impl PartialOrd for Counted { fn partial_cmp(&self, other: &Self) -> Option<Ordering> { Some(self.cmp(other)) } }
+    impl Ord for Counted { fn cmp(&self, other: &Self) -> Ordering { COMPARISONS.fetch_add(1, Atomic::SeqCst); self.0.cmp(&other.0) } }
+    impl Abomonation for Counted { }
+
+    let heap = (0 .. 256).map(|i| Counted((i * 7) % 31)).collect::<std::collections::BinaryHeap<_>>();
+    let mut bytes = Vec::new();
+    unsafe { encode(&heap, &mut bytes).unwrap(); }
+    COMPARISONS.store(0, Atomic::SeqCst);
+    let (result, _) = unsafe { decode::<std::collections::BinaryHeap<Counted>>(&mut bytes[..]) }.unwrap();
+    assert_eq!(COMPARISONS.load(Atomic::SeqCst), 0);
+    assert!(result.iter().map(|x| x.0).eq(heap.iter().map(|x| x.0)));
+}
+
+fn _frozen_hash_map() -> FrozenHashMap<String, Vec<u64>> {
+    (0 .. 1000u64).map(|i| (format!("{:08}", i), (0 .. i % 7).collect())).collect()
+}
+
+fn _frozen_btree_map() -> FrozenBTreeMap<u64, String> {
+    (0 .. 1000u64).map(|i| (i * 3, format!("{}", i))).collect()
+}
+
+#[test]
+fn test_frozen_hash_map_lookup() {
+    let map = (0 .. 1000u64).map(|i| (format!("{}", i), i)).collect::<std::collections::HashMap<_,_>>();
+    let mut bytes = Vec::new();
+    unsafe { encode(&FrozenHashMap::from(map.clone()), &mut bytes).unwrap(); }
+    let (frozen, _) = unsafe { decode::<FrozenHashMap<String, u64>>(&mut bytes[..]) }.unwrap();
+    assert_eq!(frozen.len(), map.len());
+    for (key, val) in map.iter() {
+        assert_eq!(frozen.get(key.as_str()), Some(val));
+    }
+    assert_eq!(frozen.get("1000"), None);
+    assert_eq!(frozen.iter().count(), 1000);
+}
+
+#[test]
+fn test_frozen_btree_map_range() {
+    let map = (0 .. 1000u64).map(|i| (i * 3, format!("{}", i))).collect::<std::collections::BTreeMap<_,_>>();
+    let mut bytes = Vec::new();
+    unsafe { encode(&FrozenBTreeMap::from(map.clone()), &mut bytes).unwrap(); }
+    let (frozen, _) = unsafe { decode::<FrozenBTreeMap<u64, String>>(&mut bytes[..]) }.unwrap();
+    assert_eq!(frozen.get(&300), map.get(&300));
+    assert_eq!(frozen.get(&301), None);
+    assert!(frozen.iter().eq(map.iter()));
+    assert!(frozen.range(100 .. 200).eq(map.range(100 .. 200)));
+    assert!(frozen.range(99 ..= 201).eq(map.range(99 ..= 201)));
+    assert!(frozen.range(2990 ..).eq(map.range(2990 ..)));
+    assert!(frozen.range(.. 7).rev().eq(map.range(.. 7).rev()));
+    #[allow(clippy::reversed_empty_ranges)]
+    let empty = 200 .. 100;
+    assert_eq!(frozen.range(empty).count(), 0);
+}
+
+fn _flat_map() -> FlatMap<String, Vec<u64>> {
+    (0 .. 1000u64).map(|i| (format!("{:08}", i), (0 .. i % 7).collect())).collect()
+}
+
+fn _flat_set() -> FlatSet<String> {
+    (0 .. 1000u64).map(|i| format!("{}", (i * 7) % 501)).collect()
+}
+
+#[test]
+fn test_flat_map_ops() {
+    let mut flat = (0 .. 100u64).rev().map(|i| (i * 2, i)).collect::<FlatMap<_,_>>();
+    let mut btree = (0 .. 100u64).rev().map(|i| (i * 2, i)).collect::<std::collections::BTreeMap<_,_>>();
+    assert_eq!(flat.insert(3, 3), btree.insert(3, 3));
+    assert_eq!(flat.insert(4, 7), btree.insert(4, 7));
+    assert_eq!(flat.remove(&6), btree.remove(&6));
+    assert_eq!(flat.remove(&7), btree.remove(&7));
+    let batch = (0 .. 300u64).map(|i| ((i * 37) % 250, i)).collect::<Vec<_>>();
+    flat.extend(batch.iter().cloned());
+    btree.extend(batch.iter().cloned());
+    flat.extend((1000 .. 1010u64).map(|i| (i, i)));
+    btree.extend((1000 .. 1010u64).map(|i| (i, i)));
+    assert!(flat.iter().eq(btree.iter()));
+
+    let mut bytes = Vec::new();
+    unsafe { encode(&flat, &mut bytes).unwrap(); }
+    let (result, _) = unsafe { decode::<FlatMap<u64, u64>>(&mut bytes[..]) }.unwrap();
+    for key in 0 .. 1100 {
+        assert_eq!(result.get(&key), btree.get(&key));
+    }
+}
+
+#[test]
+fn test_flat_set_ops() {
+    let mut flat = (0 .. 100u64).map(|i| (i * 13) % 64).collect::<FlatSet<_>>();
+    let mut btree = (0 .. 100u64).map(|i| (i * 13) % 64).collect::<std::collections::BTreeSet<_>>();
+    assert_eq!(flat.insert(100), btree.insert(100));
+    assert_eq!(flat.insert(5), btree.insert(5));
+    assert_eq!(flat.remove(&6), btree.remove(&6));
+    flat.extend((50 .. 150).rev());
+    btree.extend((50 .. 150).rev());
+    assert!(flat.iter().eq(btree.iter()));
+    assert!((0 .. 200).all(|i| flat.contains(&i) == btree.contains(&i)));
+}
+
+// ordered by `key` alone, so that equal values may be told apart by `tag`.
+#[derive(Clone, Debug)]
+struct Keyed { key: u64, tag: u64 }
+impl PartialEq for Keyed { fn eq(&self, other: &Self) -> bool { self.key == other.key } }
+impl Eq for Keyed { }
+impl PartialOrd for Keyed { fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> { Some(self.cmp(other)) } }
+impl Ord for Keyed { fn cmp(&self, other: &Self) -> std::cmp::Ordering { self.key.cmp(&other.key) } }
+
+#[test]
+fn test_flat_keeps_equal_keys() {
+    // `insert`, `extend`, and `collect` agree with each other, and with inserting into the
+    // standard collections (whose `collect` instead keeps the last of equal keys).
+    let batch = (0 .. 30u64).map(|tag| Keyed { key: tag % 10, tag }).collect::<Vec<_>>();
+    let tags = |iter: &mut dyn Iterator<Item=&Keyed>| iter.map(|keyed| keyed.tag).collect::<Vec<_>>();
+
+    let mut inserted = FlatSet::new();
+    for keyed in batch.iter().cloned() { inserted.insert(keyed); }
+    let mut extended = (0 .. 5u64).map(|key| Keyed { key, tag: 100 + key }).collect::<FlatSet<_>>();
+    extended.extend(batch.iter().cloned());
+    let mut btree = (0 .. 5u64).map(|key| Keyed { key, tag: 100 + key }).collect::<std::collections::BTreeSet<_>>();
+    btree.extend(batch.iter().cloned());
+    assert_eq!(tags(&mut inserted.iter()), tags(&mut batch.iter().cloned().collect::<FlatSet<_>>().iter()));
+    let mut standard = std::collections::BTreeSet::new();
+    standard.extend(batch.iter());
+    assert_eq!(tags(&mut inserted.iter()), tags(&mut standard.into_iter()));
+    assert_eq!(tags(&mut extended.iter()), tags(&mut btree.iter()));
+
+    let mut inserted = FlatMap::new();
+    for keyed in batch.iter().cloned() { inserted.insert(keyed.clone(), keyed.tag); }
+    let mut extended = (0 .. 5u64).map(|key| (Keyed { key, tag: 100 + key }, 0)).collect::<FlatMap<_,_>>();
+    extended.extend(batch.iter().map(|keyed| (keyed.clone(), keyed.tag)));
+    let mut btree = (0 .. 5u64).map(|key| (Keyed { key, tag: 100 + key }, 0)).collect::<std::collections::BTreeMap<_,_>>();
+    btree.extend(batch.iter().map(|keyed| (keyed.clone(), keyed.tag)));
+    let collected = batch.iter().map(|keyed| (keyed.clone(), keyed.tag)).collect::<FlatMap<_,_>>();
+    let entries = |iter: &mut dyn Iterator<Item=(&Keyed, &u64)>| iter.map(|(keyed, val)| (keyed.tag, *val)).collect::<Vec<_>>();
+    assert_eq!(entries(&mut inserted.iter()), entries(&mut collected.iter()));
+    let mut standard = std::collections::BTreeMap::new();
+    standard.extend(batch.iter().map(|keyed| (keyed.clone(), keyed.tag)));
+    assert_eq!(entries(&mut (&inserted).into_iter()), entries(&mut standard.iter()));
+    assert_eq!(entries(&mut extended.iter()), entries(&mut btree.iter()));
+}
+
+fn _cstrings() -> Vec<std::ffi::CString> {
+    (0 .. 256).map(|i| std::ffi::CString::new(format!("grawwwwrr{}", i)).unwrap()).collect()
+}
+
+fn _box_cstrs() -> Vec<Box<std::ffi::CStr>> {
+    _cstrings().into_iter().map(|c| c.into_boxed_c_str()).collect()
+}
+
+fn _os_strings() -> Vec<std::ffi::OsString> {
+    (0 .. 256).map(|i| std::ffi::OsString::from(format!("grawwwwrr{}", i))).collect()
+}
+
+fn _path_bufs() -> Vec<(std::path::PathBuf, u64)> {
+    (0 .. 256).map(|i| (std::path::PathBuf::from(format!("/tmp/grawwwwrr/{}.txt", i)), i)).collect()
+}
+
+#[test]
+fn test_cstring_interior_nul() {
+    let mut bytes = Vec::new();
+    unsafe { encode(&vec![std::ffi::CString::new("grawwwwrr!").unwrap()], &mut bytes).unwrap(); }
+    let length = bytes.len();
+    bytes[length - 4] = 0;
+    assert!(unsafe { decode::<Vec<std::ffi::CString>>(&mut bytes[..]) }.is_none());
+    let mut bytes = Vec::new();
+    unsafe { encode(&std::ffi::CString::new("grawwwwrr!").unwrap().into_boxed_c_str(), &mut bytes).unwrap(); }
+    let length = bytes.len();
+    bytes[length - 1] = b'!';
+    assert!(unsafe { decode::<Box<std::ffi::CStr>>(&mut bytes[..]) }.is_none());
+}
+
+// ranges whose ends have different extents.
+fn _ranges() -> Vec<std::ops::Range<String>> {
+    (0 .. 256).map(|i| format!("{}", i) .. format!("grawwwwrr{}", i * 1000)).collect()
+}
+
+fn _ranges_inclusive() -> (Vec<std::ops::RangeInclusive<String>>, std::ops::RangeInclusive<u64>) {
+    let mut exhausted = 0 ..= 3u64;
+    for _ in exhausted.by_ref() { }
+    ((0 .. 256).map(|i| format!("grawwwwrr{}", i) ..= format!("{}", i)).collect(), exhausted)
+}
+
+fn _bounds() -> Vec<std::ops::Bound<String>> {
+    use std::ops::Bound;
+    (0 .. 256).map(|i| match i % 3 {
+        0 => Bound::Included(format!("{}", i)),
+        1 => Bound::Excluded(format!("grawwwwrr{}", i)),
+        _ => Bound::Unbounded,
+    }).collect()
+}
+
+fn _wrappers() -> (std::num::Wrapping<String>, std::num::Saturating<Vec<u64>>, std::cmp::Reverse<Option<String>>) {
+    (std::num::Wrapping(format!("grawwwwr")), std::num::Saturating(vec![0, 1, 2]), std::cmp::Reverse(Some(format!("!"))))
+}
+
+fn _cow_strs() -> Vec<std::borrow::Cow<'static, str>> {
+    (0 .. 256).map(|i| if i % 2 == 0 { "grawwwwrr!".into() } else { format!("{}", i).into() }).collect()
+}
+
+fn _cow_slices() -> Vec<std::borrow::Cow<'static, [(u64, String)]>> {
+    vec![std::borrow::Cow::Borrowed(&[]), std::borrow::Cow::Owned(vec![(0, format!("grawwwwrr!")); 32])]
+}
+
+#[test]
+fn test_cow_decodes_owned() {
+    let mut bytes = Vec::new();
+    unsafe { encode(&std::borrow::Cow::Borrowed("grawwwwrr!"), &mut bytes).unwrap(); }
+    let (result, _) = unsafe { decode::<std::borrow::Cow<'static, str>>(&mut bytes[..]) }.unwrap();
+    assert!(match *result { std::borrow::Cow::Owned(ref s) => s == "grawwwwrr!", _ => false });
+}
+
+fn _times() -> Vec<(std::time::SystemTime, Timestamp, String)> {
+    use std::time::{Duration, SystemTime};
+    (0 .. 256u64).map(|i| {
+        let time = SystemTime::UNIX_EPOCH + Duration::new(i * 1_000_000, i as u32);
+        (time, Timestamp::from_nanos(-(i as i64)), format!("{}", i))
+    }).collect()
+}
+
+#[test]
+fn test_timestamp_conversions() {
+    use std::convert::TryFrom;
+    use std::time::{Duration, SystemTime};
+
+    let before = SystemTime::UNIX_EPOCH - Duration::new(5, 7);
+    let timestamp = Timestamp::try_from(before).unwrap();
+    assert_eq!(timestamp.as_nanos(), -5_000_000_007);
+    assert_eq!(SystemTime::from(timestamp), before);
+    assert!(Duration::try_from(timestamp).is_err());
+
+    let after = Duration::new(1_700_000_000, 123);
+    let timestamp = Timestamp::try_from(after).unwrap();
+    assert_eq!(Duration::try_from(timestamp), Ok(after));
+    assert_eq!(SystemTime::from(timestamp), SystemTime::UNIX_EPOCH + after);
+    assert!(Timestamp::try_from(Duration::from_secs(u64::MAX)).is_err());
+
+    assert!(Timestamp::from_nanos(-1) < Timestamp::UNIX_EPOCH);
+    assert!(Timestamp::from_nanos(256) > Timestamp::from_nanos(255));
+    assert_eq!(timestamp.checked_add(Duration::from_nanos(10)).unwrap().duration_since(timestamp), Some(Duration::from_nanos(10)));
+    assert_eq!(Timestamp::MAX.checked_add(Duration::from_nanos(1)), None);
+
+    // the layout is little-endian nanoseconds on every platform.
+    let mut bytes = Vec::new();
+    encode_pod(&Timestamp::from_nanos(0x0102030405060708), &mut bytes).unwrap();
+    assert_eq!(bytes, vec![8, 7, 6, 5, 4, 3, 2, 1]);
+}
+
+fn _counters() -> Vec<(std::sync::atomic::AtomicU64, std::sync::atomic::AtomicBool, String)> {
+    (0 .. 16u64).map(|i| (i.into(), false.into(), format!("{}", i))).collect()
+}
+
+#[test]
+fn test_atomics_update_decoded() {
+    use std::sync::atomic::Ordering;
+    type Counters = Vec<(std::sync::atomic::AtomicU64, std::sync::atomic::AtomicBool, String)>;
+
+    let mut bytes = Vec::new();
+    unsafe { encode(&_counters(), &mut bytes).unwrap(); }
+    {
+        let (counters, _) = unsafe { decode::<Counters>(&mut bytes[..]) }.unwrap();
+        std::thread::scope(|scope| {
+            for _ in 0 .. 4 {
+                scope.spawn(|| {
+                    for (count, flag, _) in counters.iter() {
+                        for _ in 0 .. 1000 { count.fetch_add(1, Ordering::Relaxed); }
+                        flag.store(true, Ordering::Relaxed);
+                    }
+                });
+            }
+        });
+    }
+    // the updates live in the bytes, and are seen by a later decode.
+    let (counters, _) = unsafe { decode::<Counters>(&mut bytes[..]) }.unwrap();
+    for (index, (count, flag, name)) in counters.iter().enumerate() {
+        assert_eq!(count.load(Ordering::Relaxed), index as u64 + 4000);
+        assert!(flag.load(Ordering::Relaxed));
+        assert_eq!(name, &format!("{}", index));
+    }
+}
+
+#[test]
+fn test_cell_update_decoded() {
+    let mut bytes = Vec::new();
+    unsafe { encode(&vec![std::cell::Cell::new(0u32); 8], &mut bytes).unwrap(); }
+    let (cells, _) = unsafe { decode::<Vec<std::cell::Cell<u32>>>(&mut bytes[..]) }.unwrap();
+    for (index, cell) in cells.iter().enumerate() { cell.set(index as u32); }
+    assert!(cells.iter().map(|cell| cell.get()).eq(0 .. 8));
+}
+
+#[test]
+fn test_cell_update_abomonated() {
+    use abomonation::abomonated::Abomonated;
+    let decoded = unsafe { Abomonated::from_value(&vec![std::cell::Cell::new(0u64); 8]) }.unwrap();
+    for (index, cell) in decoded.iter().enumerate() { cell.set(index as u64); }
+    assert!(decoded.iter().map(|cell| cell.get()).eq(0 .. 8));
+    let rows = decoded.map(|cells| &cells[2 ..]);
+    rows[0].set(7);
+    assert!(rows.iter().map(|cell| cell.get()).eq([7, 3, 4, 5, 6, 7].iter().cloned()));
+}
+
+#[test]
+fn test_encode_as() {
+    let strings = (0 .. 256).map(|i| format!("grawwwwrr{}", i)).collect::<Vec<_>>();
+    let owned = strings.iter().enumerate().map(|(i, s)| (i as u64, s.clone(), Some(vec![i as u8; i % 5]))).collect::<Vec<_>>();
+    let view = strings.iter().enumerate().map(|(i, s)| (i as u64, &s[..], Some(vec![i as u8; i % 5]))).collect::<Vec<_>>();
+    _test_pass_as::<Vec<(u64, String, Option<Vec<u8>>)>, _>(&view[..], &owned);
+
+    let names = strings.iter().map(|s| &s[..]).collect::<Vec<_>>();
+    _test_pass_as::<Vec<String>, _>(&names[..], &strings);
+    _test_pass_as::<Option<Vec<String>>, _>(&Some(&strings), &Some(strings.clone()));
+    _test_pass_as::<Option<String>, _>(&None::<&str>, &None);
+    _test_pass_as::<(Vec<String>, MyStruct), _>(&(&names, &_my_struct()), &(strings.clone(), _my_struct()));
+}
+
+fn _my_struct() -> MyStruct { MyStruct { a: "test".to_owned(), b: 0, c: vec![0, 1, 2] } }
+
+fn _test_pass_as<T: Abomonation+Eq+std::fmt::Debug, V: ?Sized+Entomb<T>>(view: &V, owned: &T) {
+    let mut bytes = Vec::new();
+    unsafe { encode_as::<T, V, _>(view, &mut bytes).unwrap(); }
+    assert_eq!(bytes.len(), measure_as::<T, V>(view));
+    assert_eq!(bytes.len(), measure(owned));
+    let (result, rest) = unsafe { decode::<T>(&mut bytes[..]) }.unwrap();
+    assert_eq!(result, owned);
+    assert!(rest.len() == 0);
+}
+
+#[test]
+fn test_vec_encoder() {
+    let records = (0 .. 256u64).map(|i| (i, format!("{:08}", i), vec![i; (i % 7) as usize])).collect::<Vec<_>>();
+    let mut expected = Vec::new();
+    unsafe { encode(&records, &mut expected).unwrap(); }
+
+    // a `Vec<u8>` writes only in order, as a pipe or socket would.
+    let mut encoder = VecEncoder::new(records.len(), Vec::new()).unwrap();
+    for record in records.iter() {
+        unsafe { encoder.push(record).unwrap(); }
+    }
+    assert_eq!(encoder.remaining(), 0);
+    for record in records.iter() {
+        unsafe { encoder.push_owned(record).unwrap(); }
+    }
+    let mut bytes = encoder.finish().unwrap();
+
+    assert_eq!(bytes, expected);
+
+    let mut iterated = Vec::new();
+    unsafe { encode_iter::<(u64, String, Vec<u64>), _, _>(records.iter(), &mut iterated).unwrap(); }
+    assert_eq!(iterated, bytes);
+
+    let (result, rest) = unsafe { decode::<Vec<(u64, String, Vec<u64>)>>(&mut bytes[..]) }.unwrap();
+    assert!(result == &records);
+    assert!(rest.len() == 0);
+}
+
+#[test]
+fn test_vec_encoder_shared() {
+    use std::rc::Rc;
+
+    let shared = Rc::new(format!("grawwwwr"));
+    let records = vec![(0u64, shared.clone()), (1, Rc::new(format!("x"))), (2, shared)];
+    let mut expected = Vec::new();
+    unsafe { encode(&records, &mut expected).unwrap(); }
+
+    let mut encoder = VecEncoder::new(records.len(), Vec::new()).unwrap();
+    for record in records.iter() {
+        unsafe { encoder.push(record).unwrap(); }
+    }
+    for record in records.iter() {
+        unsafe { encoder.push_owned(record).unwrap(); }
+    }
+    let mut bytes = encoder.finish().unwrap();
+
+    // targets shared between elements are written once.
+    assert_eq!(bytes, expected);
+    let (result, _) = unsafe { decode::<Vec<(u64, Rc<String>)>>(&mut bytes[..]) }.unwrap();
+    assert!(result == &records);
+    assert!(Rc::ptr_eq(&result[0].1, &result[2].1));
+}
+
+#[test]
+fn test_vec_encoder_length() {
+    let mut bytes = Vec::new();
+    let mut encoder = VecEncoder::<String, _>::new(2, &mut bytes).unwrap();
+    unsafe { encoder.push(&format!("grawwwwrr!")).unwrap(); }
+    // owned data follow the representations of all elements.
+    assert!(matches!(unsafe { encoder.push_owned(&format!("grawwwwrr!")) }, Err(EncodeError::ExtentMismatch { .. })));
+    unsafe { encoder.push(&format!("moo")).unwrap(); }
+    assert!(matches!(unsafe { encoder.push(&format!("oink")) }, Err(EncodeError::ExtentMismatch { .. })));
+    unsafe { encoder.push_owned(&format!("grawwwwrr!")).unwrap(); }
+    unsafe { encoder.push_owned(&format!("moo")).unwrap(); }
+    assert!(matches!(unsafe { encoder.push_owned(&format!("oink")) }, Err(EncodeError::ExtentMismatch { .. })));
+    encoder.finish().unwrap();
+
+    let mut bytes = Vec::new();
+    let mut encoder = VecEncoder::<String, _>::new(1, &mut bytes).unwrap();
+    unsafe { encoder.push(&format!("moo")).unwrap(); }
+    assert!(matches!(encoder.finish(), Err(EncodeError::ExtentMismatch { .. })));
+
+    // elements without indirection own no data to push.
+    let mut bytes = Vec::new();
+    let mut encoder = VecEncoder::<u64, _>::new(2, &mut bytes).unwrap();
+    unsafe { encoder.push(&0).unwrap(); }
+    assert!(matches!(encoder.finish(), Err(EncodeError::ExtentMismatch { .. })));
+    let mut bytes = Vec::new();
+    let mut encoder = VecEncoder::<u64, _>::new(1, &mut bytes).unwrap();
+    unsafe { encoder.push(&0).unwrap(); }
+    encoder.finish().unwrap();
+}
+
+// an iterator misreporting its length.
+#[derive(Clone)]
+struct Misreport(std::ops::Range<u64>, usize);
+impl Iterator for Misreport {
+    type Item = u64;
+    fn next(&mut self) -> Option<u64> { self.0.next() }
+    fn size_hint(&self) -> (usize, Option<usize>) { (self.1, Some(self.1)) }
+}
+impl ExactSizeIterator for Misreport { }
+
+#[test]
+fn test_encode_iter() {
+    let mut bytes = std::io::Cursor::new(Vec::new());
+    unsafe { encode_iter::<u64, _, _>((0 .. 1024u32).map(u64::from), &mut bytes).unwrap(); }
+    let (result, rest) = unsafe { decode::<Vec<u64>>(bytes.get_mut()) }.unwrap();
+    assert!(result.iter().cloned().eq(0 .. 1024));
+    assert!(rest.len() == 0);
+
+    let mut bytes = std::io::Cursor::new(Vec::new());
+    assert!(matches!(unsafe { encode_iter::<u64, _, _>(Misreport(0 .. 10, 9), &mut bytes) }, Err(EncodeError::ExtentMismatch { .. })));
+    let mut bytes = std::io::Cursor::new(Vec::new());
+    assert!(matches!(unsafe { encode_iter::<u64, _, _>(Misreport(0 .. 10, 11), &mut bytes) }, Err(EncodeError::ExtentMismatch { .. })));
+}
+
+#[test]
+fn test_decode_view() {
+    let records = (0 .. 256u64).map(|i| (i, format!("{:08}", i), if i % 2 == 0 { Some(vec![format!("{:08}", i); (i % 5) as usize]) } else { None })).collect::<Vec<_>>();
+    let mut bytes = Vec::new();
+    unsafe { encode(&records, &mut bytes).unwrap(); }
+    let (view, rest) = unsafe { decode_view::<Vec<(u64, String, Option<Vec<String>>)>>(&mut bytes[..]) }.unwrap();
+    assert!(rest.len() == 0);
+    assert_eq!(view.len(), records.len());
+    for (record, (number, name, others)) in records.iter().zip(view.iter()) {
+        let name: &str = name;
+        assert_eq!(number, record.0);
+        assert_eq!(name, record.1);
+        assert_eq!(others.map(|others| others.iter().collect::<Vec<&str>>()), record.2.as_ref().map(|others| others.iter().map(|s| &s[..]).collect()));
+    }
+    assert!(view.as_slice() == &records[..]);
+
+    let mut bytes = Vec::new();
+    unsafe { encode(&format!("grawwwwrr!").into_boxed_str(), &mut bytes).unwrap(); }
+    assert_eq!(unsafe { decode_view::<Box<str>>(&mut bytes[..]) }.map(|(view, _)| view), Some("grawwwwrr!"));
+
+    // types implemented through `unsafe_abomonate!`, arrays, and others viewed by reference.
+    let records = (0 .. 32u64).map(|i| (_my_struct(), [i; 3], std::ffi::CString::new(format!("{}", i)).unwrap().into_boxed_c_str())).collect::<Vec<_>>();
+    let mut bytes = Vec::new();
+    unsafe { encode(&records, &mut bytes).unwrap(); }
+    let (view, _) = unsafe { decode_view::<Vec<(MyStruct, [u64; 3], Box<std::ffi::CStr>)>>(&mut bytes[..]) }.unwrap();
+    for (record, (my_struct, array, name)) in records.iter().zip(view.iter()) {
+        let (my_struct, array, name): (&MyStruct, &[u64; 3], &std::ffi::CStr) = (my_struct, array, name);
+        assert!(my_struct == &record.0);
+        assert_eq!(array, &record.1);
+        assert_eq!(name, &*record.2);
+    }
+
+    let cows: (std::borrow::Cow<'static, str>, std::borrow::Cow<'static, [String]>) = (std::borrow::Cow::Borrowed("grawwwwr"), std::borrow::Cow::Owned(vec![format!("a"), format!("b")]));
+    let mut bytes = Vec::new();
+    unsafe { encode(&cows, &mut bytes).unwrap(); }
+    let ((name, names), _) = unsafe { decode_view::<(std::borrow::Cow<'static, str>, std::borrow::Cow<'static, [String]>)>(&mut bytes[..]) }.unwrap();
+    assert_eq!(name, "grawwwwr");
+    assert!(names.iter().eq(vec!["a", "b"]));
+}
+
+#[test]
+fn test_decode_view_std() {
+    use std::num::{NonZeroU64, Wrapping};
+    use std::ops::Bound;
+    use std::rc::Rc;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    // components viewed by copy, component-wise, and by reference.
+    type Record = (NonZeroU64, Result<String, u64>, std::ops::Range<String>, Bound<String>, Wrapping<u64>, AtomicU64, std::net::Ipv4Addr);
+    let record: Record = (NonZeroU64::new(3).unwrap(), Ok(format!("grawwwwr")), format!("a") .. format!("b"), Bound::Excluded(format!("c")), Wrapping(5), AtomicU64::new(7), std::net::Ipv4Addr::LOCALHOST);
+    let mut bytes = Vec::new();
+    unsafe { encode(&record, &mut bytes).unwrap(); }
+    let ((number, result, range, bound, wrapped, atomic, address), _) = unsafe { decode_view::<Record>(&mut bytes[..]) }.unwrap();
+    let (result, range, bound): (Result<&str, u64>, std::ops::Range<&str>, Bound<&str>) = (result, range, bound);
+    assert_eq!(number.get(), 3);
+    assert_eq!(result, Ok("grawwwwr"));
+    assert_eq!(range, "a" .. "b");
+    assert_eq!(bound, Bound::Excluded("c"));
+    assert_eq!(wrapped, Wrapping(5));
+    assert_eq!(atomic.load(Ordering::Relaxed), 7);
+    assert_eq!(address, std::net::Ipv4Addr::LOCALHOST);
+
+    // sequences are viewed as one or more `Slice`s.
+    let mut deque = (0 .. 8u64).collect::<std::collections::VecDeque<_>>();
+    deque.rotate_left(3);
+    let heap = (0 .. 8u64).collect::<std::collections::BinaryHeap<_>>();
+    let shared: Arc<[String]> = vec![format!("a"), format!("b")].into();
+    let record = (deque, heap, Rc::new(format!("grawwwwr")), shared);
+    let mut bytes = Vec::new();
+    unsafe { encode(&record, &mut bytes).unwrap(); }
+    let ((deque, heap, name, shared), _) = unsafe { decode_view::<(std::collections::VecDeque<u64>, std::collections::BinaryHeap<u64>, Rc<String>, Arc<[String]>)>(&mut bytes[..]) }.unwrap();
+    assert!(deque.0.iter().chain(deque.1.iter()).eq(record.0.iter().cloned()));
+    assert!(heap.iter().eq(record.1.iter().cloned()));
+    assert_eq!(name, "grawwwwr");
+    assert!(shared.iter().eq(vec!["a", "b"]));
+
+    let map = (0 .. 8u64).map(|i| (i, format!("{}", i))).collect::<FlatMap<_, _>>();
+    let mut bytes = Vec::new();
+    unsafe { encode(&map, &mut bytes).unwrap(); }
+    let (view, _) = unsafe { decode_view::<FlatMap<u64, String>>(&mut bytes[..]) }.unwrap();
+    assert!(view == &map);
+}
+
+fn _test_pass_heap<T: Abomonation+Ord+Clone>(record: std::collections::BinaryHeap<T>) {
+    let mut bytes = Vec::new();
+    unsafe { encode(&record, &mut bytes).unwrap(); }
+    {
+        let (result, rest) = unsafe { decode::<std::collections::BinaryHeap<T>>(&mut bytes[..]) }.unwrap();
+        assert!(result.iter().eq(record.iter()));
+        assert!(result.peek() == record.peek());
+        assert!(rest.len() == 0);
+    }
+}
+
 fn _test_pass<T: Abomonation+Eq>(record: T) {
     let mut bytes = Vec::new();
     unsafe { encode(&record, &mut bytes).unwrap(); }
@@ -62,7 +682,7 @@ fn _test_size<T: Abomonation>(record: T) {
 }
 
 
-#[derive(Eq, PartialEq)]
+#[derive(Eq, PartialEq, Debug)]
 struct MyStruct {
     a: String,
     b: u64,
@@ -128,3 +748,578 @@ fn test_net_types() {
     let (t, r) = unsafe { decode::<SocketAddr>(&mut bytes) }.unwrap(); assert!(*t == socket_addr4);
     let (t, _r) = unsafe { decode::<SocketAddr>(r) }.unwrap(); assert!(*t == socket_addr6);
 }
+
+#[test]
+fn test_abomonated_map() {
+    use abomonation::abomonated::Abomonated;
+
+    let record = ((3u64, format!("headline")), vec![(0u64, format!("grawwwwrr!")); 32]);
+
+    let mut bytes = Vec::new();
+    unsafe { encode(&record, &mut bytes).unwrap(); }
+
+    let decoded = unsafe { Abomonated::<((u64, String), Vec<(u64, String)>),_>::new(bytes) }.unwrap();
+    let rows = decoded.map(|x| &x.1);
+    assert!(*rows == record.1);
+
+    // moving the projection must not invalidate it.
+    let rows = Box::new(rows);
+    let slice = rows.map(|x| &x[8..]);
+    assert!(*slice == record.1[8..]);
+}
+
+#[test]
+fn test_abomonated_split_elements() {
+    use abomonation::abomonated::Abomonated;
+
+    let record = (0..64u64).map(|i| (i, format!("{}", i))).collect::<Vec<_>>();
+
+    let mut bytes = Vec::new();
+    unsafe { encode(&record, &mut bytes).unwrap(); }
+
+    let decoded = unsafe { Abomonated::<Vec<(u64, String)>,_>::new(bytes) }.unwrap();
+    let elements = decoded.split_elements();
+    assert_eq!(elements.len(), record.len());
+
+    let threads = elements
+        .into_iter()
+        .map(|element| std::thread::spawn(move || (element.0, element.1.clone())))
+        .collect::<Vec<_>>();
+
+    for (thread, expected) in threads.into_iter().zip(record.iter()) {
+        assert!(&thread.join().unwrap() == expected);
+    }
+}
+
+#[test]
+fn test_abomonated_from_value() {
+    use abomonation::abomonated::Abomonated;
+
+    // strings of eight bytes keep the following `u64` data aligned.
+    let record = vec![(format!("01234567"), vec![1u64, 2, 3]); 32];
+    let decoded = unsafe { Abomonated::from_value(&record) }.unwrap();
+    assert!(*decoded == record);
+    assert_eq!(decoded.as_bytes().len(), measure(&record));
+    assert_eq!(decoded.as_bytes().as_ptr() as usize % std::mem::align_of::<Vec<(String, Vec<u64>)>>(), 0);
+
+    // zero-sized values decode from no bytes at all.
+    let mut empty = unsafe { Abomonated::from_value(&()) }.unwrap();
+    let () = *empty;
+    let () = *empty.as_mut();
+    assert!(empty.as_bytes().is_empty());
+}
+
+#[test]
+fn test_abomonated_from_value_aligns() {
+    use abomonation::abomonated::Abomonated;
+
+    // strings of odd lengths would misalign the following data without padding.
+    let record = (0 .. 16).map(|i| (format!("{}", "x".repeat(i)), vec![i as u128; i % 3], Box::new(i as u64))).collect::<Vec<_>>();
+    let decoded = unsafe { Abomonated::from_value(&record) }.unwrap();
+    assert!(*decoded == record);
+    for (_, values, boxed) in decoded.iter() {
+        assert_eq!(values.as_ptr() as usize % std::mem::align_of::<u128>(), 0);
+        assert_eq!(&**boxed as *const u64 as usize % std::mem::align_of::<u64>(), 0);
+    }
+}
+
+#[test]
+fn test_abomonated_from_value_rc() {
+    use abomonation::abomonated::Abomonated;
+    match unsafe { Abomonated::from_value(&std::rc::Rc::new(0u64)) } {
+        Err(EncodeError::Io(error)) => assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput),
+        _ => panic!("encoded a shared pointer"),
+    }
+}
+
+// writes one more byte each time it is entombed.
+struct Growing(std::cell::Cell<usize>);
+
+impl Abomonation for Growing {
+    unsafe fn entomb<W: ::std::io::Write>(&self, write: &mut W) -> ::std::io::Result<()> {
+        self.0.set(self.0.get() + 1);
+        write.write_all(&vec![0u8; self.0.get()])
+    }
+    unsafe fn exhume<'b>(&mut self, bytes: &'b mut [u8]) -> Option<&'b mut [u8]> { Some(bytes) }
+    fn extent(&self) -> usize { self.0.get() }
+}
+
+#[test]
+fn test_abomonated_from_value_mismatch() {
+    use abomonation::abomonated::Abomonated;
+    match unsafe { Abomonated::from_value(&Growing(std::cell::Cell::new(0))) } {
+        Err(EncodeError::ExtentMismatch { type_name }) => assert!(type_name.ends_with("Growing")),
+        _ => panic!("passes disagreed without an error"),
+    }
+}
+
+#[test]
+fn test_abomonated_as_mut() {
+    use abomonation::abomonated::Abomonated;
+
+    let record = (0u64, vec![(vec![1u32, 2, 3], Some(Box::new([0u8; 4]))), (vec![], None)], format!("abc"));
+    let mut decoded = unsafe { Abomonated::from_value(&record) }.unwrap();
+
+    {
+        let (mut counter, rows, mut text) = decoded.as_mut().fields();
+        *counter += 7;
+        for (index, row) in rows.elements().into_iter_mut().enumerate() {
+            let (mut values, boxed) = row.fields();
+            for value in values.as_mut_slice() { *value *= 2; }
+            if let Some(boxed) = boxed.as_mut() {
+                *boxed.contents() = [index as u8 + 1; 4];
+            }
+        }
+        text.as_mut_str().make_ascii_uppercase();
+    }
+
+    assert_eq!(decoded.0, 7);
+    assert_eq!(decoded.1[0].0, vec![2, 4, 6]);
+    assert_eq!(decoded.1[0].1, Some(Box::new([1u8; 4])));
+    assert_eq!(decoded.1[1].1, None);
+    assert_eq!(decoded.2, "ABC");
+}
+
+assert_abomonable!(MyStruct, Vec<MyStruct>, Option<Box<MyStruct>>);
+
+#[test]
+fn test_has_indirection() {
+    const { assert!(!<(u32, u32, u32, u32)>::HAS_INDIRECTION) };
+    const { assert!(!<[Option<(u8, u64)>; 4]>::HAS_INDIRECTION) };
+    const { assert!(<MyStruct>::HAS_INDIRECTION) };
+    const { assert!(<Vec<u64>>::HAS_INDIRECTION) };
+    const { assert!(<(u64, Option<String>)>::HAS_INDIRECTION) };
+}
+
+#[test]
+fn test_vec_no_indirection_extent() {
+    // would visit each of the 2^40 elements without the fast path.
+    let record = vec![(); 1 << 40];
+    assert_eq!(measure(&record), std::mem::size_of::<Vec<()>>());
+    _test_pass(record);
+}
+
+#[test]
+fn test_encode_decode_slice() {
+    let record = vec![(0u64, format!("grawwwwrr!")); 32];
+
+    let mut bytes = Vec::new();
+    unsafe { encode_slice(&record[4..], &mut bytes).unwrap(); }
+    unsafe { encode_str("grawwwwr", &mut bytes).unwrap(); }
+    unsafe { encode_slice::<u64, _>(&[], &mut bytes).unwrap(); }
+
+    let (t, r) = unsafe { decode_slice::<(u64, String)>(&mut bytes) }.unwrap(); assert!(t == &record[4..]);
+    let (t, r) = unsafe { decode_str(r) }.unwrap(); assert!(t == "grawwwwr");
+    let (t, r) = unsafe { decode_slice::<u64>(r) }.unwrap(); assert!(t.is_empty());
+    assert!(r.is_empty());
+
+    let mut bytes = Vec::new();
+    unsafe { encode_slice(&record[..], &mut bytes).unwrap(); }
+    bytes.pop();
+    assert!(unsafe { decode_slice::<(u64, String)>(&mut bytes) }.is_none());
+}
+
+#[test] fn test_rc_pass() { let shared = std::rc::Rc::new(format!("grawwwwrr!")); _test_pass(vec![shared; 32]); }
+#[test] fn test_arc_pass() { _test_pass((0 .. 32).map(|i| std::sync::Arc::new(vec![i; i])).collect::<Vec<_>>()); }
+#[test] fn test_rc_str_pass() { _test_pass(vec![std::rc::Rc::<str>::from("grawwwwrr!"); 32]); }
+#[test] fn test_arc_slice_pass() { _test_pass(vec![std::sync::Arc::<[String]>::from(vec![format!("grawwwwrr!"); 8]); 32]); }
+#[test] fn test_rc_fail() { let shared = std::rc::Rc::new(format!("grawwwwrr!")); _test_fail(vec![shared; 32]); }
+#[test] fn test_rc_size() { _test_size((0 .. 32).map(|i| std::rc::Rc::new(vec![i as u8; i])).collect::<Vec<_>>()); }
+#[test] fn test_arc_slice_size() { _test_size(vec![std::sync::Arc::<[String]>::from(vec![format!("grawwwwrr!"); 8]); 32]); }
+
+#[test]
+fn test_rc_reference_type_checked() {
+    use std::rc::Rc;
+
+    let shared = Rc::new(7u64);
+    let mut bytes = Vec::new();
+    unsafe { encode(&(shared.clone(), shared), &mut bytes).unwrap(); }
+
+    // the second pointer refers to a target exhumed as another type.
+    assert!(unsafe { decode::<(Rc<u64>, Rc<i64>)>(&mut bytes.clone()) }.is_none());
+    assert!(unsafe { decode::<(Rc<u64>, Rc<u64>)>(&mut bytes) }.is_some());
+}
+
+#[test]
+fn test_rc_extent_within_entomb() {
+    use std::io::{Result as IOResult, Write};
+    use std::rc::Rc;
+
+    // measures its pointer while entombing it, which must not disturb deduplication.
+    #[derive(PartialEq)]
+    struct Measuring(Rc<String>);
+    impl Abomonation for Measuring {
+        unsafe fn entomb<W: Write>(&self, write: &mut W) -> IOResult<()> {
+            assert!(self.0.extent() > 0);
+            self.0.entomb(write)
+        }
+        unsafe fn exhume<'b>(&mut self, bytes: &'b mut [u8]) -> Option<&'b mut [u8]> {
+            self.0.exhume(bytes)
+        }
+        fn extent(&self) -> usize { self.0.extent() }
+    }
+
+    let shared = Rc::new(format!("grawwwwr"));
+    let record = vec![Measuring(shared.clone()), Measuring(Rc::new(format!("x"))), Measuring(shared)];
+    let mut bytes = Vec::new();
+    unsafe { encode(&record, &mut bytes).unwrap(); }
+    assert_eq!(bytes.len(), measure(&record));
+    let (result, rest) = unsafe { decode::<Vec<Measuring>>(&mut bytes) }.unwrap();
+    assert!(result == &record);
+    assert!(rest.is_empty());
+    assert!(Rc::ptr_eq(&result[0].0, &result[2].0));
+}
+
+#[test]
+fn test_rc_shared() {
+    use std::rc::Rc;
+
+    let shared = Rc::new(vec![format!("grawwwwrr!"); 32]);
+    let record = vec![(shared.clone(), Rc::new(vec![])), (shared.clone(), shared.clone())];
+
+    let mut bytes = Vec::new();
+    unsafe { encode(&record, &mut bytes).unwrap(); }
+
+    // the shared target is written only once.
+    let mut alone = Vec::new();
+    unsafe { encode(&shared, &mut alone).unwrap(); }
+    assert!(bytes.len() < 2 * alone.len());
+
+    let (result, rest) = unsafe { decode::<Vec<(Rc<Vec<String>>, Rc<Vec<String>>)>>(&mut bytes) }.unwrap();
+    assert!(result == &record);
+    assert!(rest.is_empty());
+    assert!(Rc::ptr_eq(&result[0].0, &result[1].0));
+    assert!(Rc::ptr_eq(&result[1].0, &result[1].1));
+    assert!(!Rc::ptr_eq(&result[0].0, &result[0].1));
+
+    // clones and drops of decoded pointers do not free decoded memory.
+    let clone = result[0].0.clone();
+    assert!(Rc::strong_count(&clone) > 1);
+    drop(clone);
+    assert!(result[0].0.len() == 32);
+}
+
+struct Node {
+    value: u64,
+    values: Vec<u64>,
+    next: Option<Box<Node>>,
+}
+
+impl Abomonation for Node {
+    unsafe fn entomb<W: std::io::Write>(&self, write: &mut W) -> std::io::Result<()> {
+        self.values.entomb(write)?;
+        self.next.entomb(write)
+    }
+    unsafe fn exhume<'b>(&mut self, bytes: &'b mut [u8]) -> Option<&'b mut [u8]> {
+        let bytes = self.values.exhume(bytes)?;
+        self.next.exhume(bytes)
+    }
+    fn extent(&self) -> usize { self.values.extent() + self.next.extent() }
+}
+
+// drops iteratively, as the default drop would recurse once per node.
+impl Drop for Node {
+    fn drop(&mut self) {
+        let mut next = self.next.take();
+        while let Some(mut node) = next {
+            next = node.next.take();
+        }
+    }
+}
+
+fn _linked_list(length: u64) -> Option<Box<Node>> {
+    let mut list = None;
+    for value in (0 .. length).rev() {
+        list = Some(Box::new(Node { value, values: vec![value; (value % 3) as usize], next: list }));
+    }
+    list
+}
+
+fn _test_linked_list(length: u64) {
+    let list = _linked_list(length);
+
+    let mut bytes = Vec::new();
+    unsafe { encode(&list, &mut bytes).unwrap(); }
+    assert_eq!(bytes.len(), measure(&list));
+
+    let (result, rest) = unsafe { decode::<Option<Box<Node>>>(&mut bytes) }.unwrap();
+    assert!(rest.is_empty());
+
+    let mut cursor = result;
+    for value in 0 .. length {
+        let node = cursor.as_ref().unwrap();
+        assert_eq!(node.value, value);
+        assert_eq!(node.values, vec![value; (value % 3) as usize]);
+        cursor = &node.next;
+    }
+    assert!(cursor.is_none());
+
+    // truncated bytes fail to decode, at any depth.
+    let mut bytes = Vec::new();
+    unsafe { encode(&list, &mut bytes).unwrap(); }
+    let length = bytes.len();
+    assert!(unsafe { decode::<Option<Box<Node>>>(&mut bytes[.. length - 1]) }.is_none());
+}
+
+#[test] fn test_linked_list_short() { _test_linked_list(100); }
+#[test] fn test_linked_list_deep() { _test_linked_list(1_000_000); }
+
+// measures a list from within `entomb`, comparing against its measure taken outside.
+struct Measuring(Option<Box<Node>>, usize);
+impl Abomonation for Measuring {
+    unsafe fn entomb<W: std::io::Write>(&self, write: &mut W) -> std::io::Result<()> {
+        assert_eq!(measure(&self.0), self.1);
+        self.0.entomb(write)
+    }
+    unsafe fn exhume<'b>(&mut self, bytes: &'b mut [u8]) -> Option<&'b mut [u8]> { self.0.exhume(bytes) }
+    fn extent(&self) -> usize { self.0.extent() }
+}
+
+#[test]
+fn test_linked_list_measure_within_entomb() {
+    let list = _linked_list(10_000);
+    let length = measure(&list);
+    let record = Box::new(Measuring(list, length));
+
+    let mut bytes = Vec::new();
+    unsafe { encode(&record, &mut bytes).unwrap(); }
+    assert_eq!(bytes.len(), measure(&record));
+    let (result, rest) = unsafe { decode::<Box<Measuring>>(&mut bytes) }.unwrap();
+    assert!(rest.is_empty());
+    let mut count = 0;
+    let mut cursor = &result.0;
+    while let Some(ref node) = *cursor { count += 1; cursor = &node.next; }
+    assert_eq!(count, 10_000);
+}
+
+// a writer panicking once it has accepted `0` bytes.
+struct PanicAfter(usize);
+impl std::io::Write for PanicAfter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if buf.len() > self.0 { panic!("writer full"); }
+        self.0 -= buf.len();
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> std::io::Result<()> { Ok(()) }
+}
+
+#[test]
+fn test_linked_list_panic() {
+    let other = _linked_list(1_000);
+    let mut expected = Vec::new();
+    unsafe { encode(&other, &mut expected).unwrap(); }
+
+    let list = _linked_list(10_000);
+    let length = measure(&list);
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| unsafe { encode(&list, &mut PanicAfter(length / 2)) }));
+    assert!(result.is_err());
+
+    // the panic leaves neither a raised depth nor deferred work behind.
+    let mut bytes = Vec::new();
+    unsafe { encode(&other, &mut bytes).unwrap(); }
+    assert_eq!(bytes, expected);
+    assert_eq!(bytes.len(), measure(&other));
+    let (result, rest) = unsafe { decode::<Option<Box<Node>>>(&mut bytes) }.unwrap();
+    assert!(rest.is_empty());
+    let mut count = 0;
+    let mut cursor = result;
+    while let Some(ref node) = *cursor { count += 1; cursor = &node.next; }
+    assert_eq!(count, 1_000);
+}
+
+#[test]
+fn test_decode_limits() {
+    let record = vec![(0u64, vec![format!("grawwwwrr!"); 8]); 32];
+    let mut bytes = Vec::new();
+    unsafe { encode(&record, &mut bytes).unwrap(); }
+    bytes.extend_from_slice(&[1, 2, 3]);
+
+    let limits = DecodeLimits { max_bytes: bytes.len() - 3, max_depth: 2, max_elements: 32 };
+    let mut copy = bytes.clone();
+    let (result, rest) = unsafe { decode_with_limits::<Vec<(u64, Vec<String>)>>(&mut copy, &limits) }.unwrap();
+    assert!(result == &record);
+    assert_eq!(rest, &[1, 2, 3]);
+
+    let limits = [
+        (DecodeLimits { max_bytes: bytes.len() - 4, ..DecodeLimits::default() }, DecodeError::BytesExceeded),
+        (DecodeLimits { max_depth: 1, ..DecodeLimits::default() }, DecodeError::DepthExceeded),
+        (DecodeLimits { max_elements: 31, ..DecodeLimits::default() }, DecodeError::ElementsExceeded),
+    ];
+    for (limits, error) in limits.iter() {
+        let result = unsafe { decode_with_limits::<Vec<(u64, Vec<String>)>>(&mut bytes.clone(), limits).err() };
+        assert_eq!(result, Some(*error));
+    }
+
+    let length = bytes.len();
+    let result = unsafe { decode_with_limits::<Vec<(u64, Vec<String>)>>(&mut bytes[.. length - 4], &DecodeLimits::default()) };
+    assert_eq!(result.err(), Some(DecodeError::Invalid));
+}
+
+#[test]
+fn test_decode_limits_truncation() {
+    // a capacity other than three, so that only the length matches.
+    let mut string = String::with_capacity(8);
+    string.push_str("xyz");
+    let mut bytes = Vec::new();
+    unsafe { encode(&vec![string], &mut bytes).unwrap(); }
+    let word = std::mem::size_of::<usize>();
+    let field = (3 .. 6).find(|&index| bytes[word * index .. word * (index + 1)] == 3usize.to_ne_bytes()).unwrap();
+    let limits = DecodeLimits { max_bytes: bytes.len() - 1, ..DecodeLimits::default() };
+
+    // the string is cut short by `max_bytes`, but not by the bytes themselves.
+    let result = unsafe { decode_with_limits::<Vec<String>>(&mut bytes.clone(), &limits).err() };
+    assert_eq!(result, Some(DecodeError::BytesExceeded));
+
+    // no number of bytes would hold a string claiming more than all of them.
+    let length = bytes.len();
+    bytes[word * field .. word * (field + 1)].copy_from_slice(&length.to_ne_bytes());
+    let result = unsafe { decode_with_limits::<Vec<String>>(&mut bytes.clone(), &limits).err() };
+    assert_eq!(result, Some(DecodeError::Invalid));
+}
+
+#[test]
+fn test_decode_view_with_limits() {
+    let record = vec![format!("grawwwwr"); 8];
+    let mut bytes = Vec::new();
+    unsafe { encode(&record, &mut bytes).unwrap(); }
+
+    let limits = DecodeLimits { max_elements: 7, ..DecodeLimits::default() };
+    let result = unsafe { decode_view_with_limits::<Vec<String>>(&mut bytes.clone(), &limits).err() };
+    assert_eq!(result, Some(DecodeError::ElementsExceeded));
+
+    let (view, rest) = unsafe { decode_view_with_limits::<Vec<String>>(&mut bytes, &DecodeLimits::default()) }.unwrap();
+    assert!(view.iter().eq(record.iter().map(|name| &name[..])));
+    assert!(rest.is_empty());
+}
+
+#[test]
+fn test_decode_limits_hostile() {
+    // a vector of vectors of `()`, whose inner vector claims `1 << 62` elements.
+    let record = vec![vec![(); 1 << 62]];
+    let mut bytes = Vec::new();
+    unsafe { encode(&record, &mut bytes).unwrap(); }
+
+    let limits = DecodeLimits { max_elements: 1 << 20, ..DecodeLimits::default() };
+    let result = unsafe { decode_with_limits::<Vec<Vec<()>>>(&mut bytes.clone(), &limits).err() };
+    assert_eq!(result, Some(DecodeError::ElementsExceeded));
+
+    // the same length overflows the size of a vector of `u64`.
+    let result = unsafe { decode_with_limits::<Vec<Vec<u64>>>(&mut bytes, &DecodeLimits::default()) };
+    assert_eq!(result.err(), Some(DecodeError::Overflow));
+}
+
+#[test]
+fn test_decode_corrupt_lengths() {
+    // replaces the length three of the header following the outer vector's with `length`.
+    fn corrupt<T: Abomonation>(record: &Vec<T>, length: usize) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        unsafe { encode(record, &mut bytes).unwrap(); }
+        let word = std::mem::size_of::<usize>();
+        let words = std::mem::size_of::<Vec<T>>() / word;
+        let field = (words .. 2 * words).find(|&index| bytes[word * index .. word * (index + 1)] == 3usize.to_ne_bytes()).unwrap();
+        bytes[word * field .. word * (field + 1)].copy_from_slice(&length.to_ne_bytes());
+        bytes
+    }
+    // a capacity other than three, so that only the length matches.
+    let string = || { let mut string = String::with_capacity(8); string.push_str("xyz"); string };
+    for &length in [usize::MAX, 1 << 62, 1 << 40].iter() {
+        assert!(unsafe { decode::<Vec<String>>(&mut corrupt(&vec![string()], length)) }.is_none());
+        assert!(unsafe { decode::<Vec<std::ffi::OsString>>(&mut corrupt(&vec![std::ffi::OsString::from(string())], length)) }.is_none());
+        assert!(unsafe { decode::<Vec<std::path::PathBuf>>(&mut corrupt(&vec![std::path::PathBuf::from(string())], length)) }.is_none());
+        let mut vector = Vec::with_capacity(8);
+        vector.extend_from_slice(&[1u64, 2, 3]);
+        assert!(unsafe { decode::<Vec<Vec<u64>>>(&mut corrupt(&vec![vector], length)) }.is_none());
+    }
+}
+
+#[test]
+fn test_decode_limits_deep() {
+    let list = _linked_list(10_000);
+    let mut bytes = Vec::new();
+    unsafe { encode(&list, &mut bytes).unwrap(); }
+
+    // boxes and vectors both count towards the depth, including past deferred boxes.
+    let limits = DecodeLimits { max_depth: 20_000, ..DecodeLimits::default() };
+    assert!(unsafe { decode_with_limits::<Option<Box<Node>>>(&mut bytes.clone(), &limits).is_ok() });
+    let limits = DecodeLimits { max_depth: 9_999, ..DecodeLimits::default() };
+    let result = unsafe { decode_with_limits::<Option<Box<Node>>>(&mut bytes, &limits) };
+    assert_eq!(result.err(), Some(DecodeError::DepthExceeded));
+}
+
+#[test]
+fn test_encode_bounded() {
+    let record = vec![(0u64, format!("grawwwwrr!")); 32];
+    let size = measure(&record);
+
+    let mut bytes = Vec::new();
+    let error = unsafe { encode_bounded(&record, &mut bytes, size - 1) }.unwrap_err();
+    assert!(matches!(error, EncodeError::LimitExceeded { size: s, max_bytes: m } if s == size && m == size - 1));
+    assert!(bytes.is_empty());
+
+    unsafe { encode_bounded(&record, &mut bytes, size).unwrap(); }
+    let mut expected = Vec::new();
+    unsafe { encode(&record, &mut expected).unwrap(); }
+    assert_eq!(bytes, expected);
+}
+
+// reports no owned data, though it writes some.
+struct Understated(String);
+
+impl Abomonation for Understated {
+    unsafe fn entomb<W: ::std::io::Write>(&self, write: &mut W) -> ::std::io::Result<()> { self.0.entomb(write) }
+    unsafe fn exhume<'b>(&mut self, bytes: &'b mut [u8]) -> Option<&'b mut [u8]> { self.0.exhume(bytes) }
+    fn extent(&self) -> usize { 0 }
+}
+
+#[test]
+fn test_encode_bounded_understated() {
+    let record = Understated(format!("grawwwwrr!"));
+    let mut bytes = Vec::new();
+    let error = unsafe { encode_bounded(&record, &mut bytes, 1 << 10) }.unwrap_err();
+    match error {
+        EncodeError::ExtentMismatch { type_name } => assert!(type_name.ends_with("Understated")),
+        error => panic!("unexpected error: {}", error),
+    }
+    assert!(bytes.len() <= measure(&record));
+}
+
+// owns no data, but reports an extent of its choosing.
+struct Oversized(usize);
+
+impl Abomonation for Oversized {
+    fn extent(&self) -> usize { self.0 }
+}
+
+#[test]
+fn test_encode_bounded_limit() {
+    let header = std::mem::size_of::<Oversized>();
+    let mut bytes = Vec::new();
+
+    // the largest measurable size is compared with the budget.
+    let error = unsafe { encode_bounded(&Oversized(usize::MAX - header), &mut bytes, usize::MAX - 1) }.unwrap_err();
+    assert!(matches!(error, EncodeError::LimitExceeded { size: usize::MAX, max_bytes } if max_bytes == usize::MAX - 1));
+    assert!(bytes.is_empty());
+
+    // one byte more overflows the measured size.
+    let error = unsafe { encode_bounded(&Oversized(usize::MAX - header + 1), &mut bytes, usize::MAX) }.unwrap_err();
+    assert!(matches!(error, EncodeError::SizeOverflow));
+    assert!(bytes.is_empty());
+}
+
+#[test]
+fn test_encode_error() {
+    let mut bytes = std::io::Cursor::new(Vec::new());
+    assert!(matches!(VecEncoder::<u64, _>::new(usize::MAX, &mut bytes), Err(EncodeError::SizeOverflow)));
+    assert!(bytes.get_ref().is_empty());
+    let mut bytes = std::io::Cursor::new(Vec::new());
+    assert!(matches!(unsafe { encode_iter::<u64, _, _>(std::iter::repeat_n(0u64, usize::MAX), &mut bytes) }, Err(EncodeError::SizeOverflow)));
+    assert!(bytes.get_ref().is_empty());
+
+    // a writer with room for only four bytes.
+    let mut buffer = [0u8; 4];
+    let result = unsafe { encode_as::<String, _, _>("grawwwwrr!", &mut &mut buffer[..]) };
+    match result {
+        Err(EncodeError::Io(error)) => assert_eq!(error.kind(), std::io::ErrorKind::WriteZero),
+        result => panic!("unexpected result: {:?}", result),
+    }
+}